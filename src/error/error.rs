@@ -1,7 +1,7 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::{RecvError, SendError, TryRecvError, TrySendError};
-use alloc::boxed::Box;
+use super::{RecvError, SendError, SendMessageError, TryRecvError, TrySendError};
+use alloc::{boxed::Box, vec::Vec};
 use thiserror::Error;
 
 pub type Result<T = (), E = Error> = core::result::Result<T, E>;
@@ -18,7 +18,7 @@ pub enum Error {
     Send(#[from] SendError),
 
     #[error("{0}")]
-    TrySend(#[from] TrySendError),
+    TrySend(TrySendError),
 
     #[cfg(feature = "tokio")]
     #[error("{0}")]
@@ -30,6 +30,21 @@ pub enum Error {
 
     #[error("{0}")]
     Other(#[from] Box<dyn core::error::Error + Send + Sync>),
+
+    #[error("{} errors occurred", .0.len())]
+    Aggregate(Vec<Error>),
+}
+
+impl<T> From<SendMessageError<T>> for Error {
+    fn from(value: SendMessageError<T>) -> Self {
+        Error::Send(value.error())
+    }
+}
+
+impl<T> From<TrySendError<T>> for Error {
+    fn from(value: TrySendError<T>) -> Self {
+        Error::TrySend(value.without_message())
+    }
 }
 
 #[cfg(feature = "serde")]
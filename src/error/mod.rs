@@ -1,5 +1,6 @@
 // This is free and unencumbered software released into the public domain.
 
+#[allow(clippy::module_inception)]
 mod error;
 pub use error::*;
 
@@ -3,18 +3,35 @@
 use thiserror::Error;
 
 #[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
-#[error("RecvError")]
-pub struct RecvError;
+pub enum RecvError {
+    #[error("failed to receive message on unconnected port")]
+    Unconnected,
+
+    #[error("failed to receive message on disconnected port")]
+    Disconnected,
+
+    #[error("failed to receive message on closed port")]
+    Closed,
+
+    #[error("cannot block on receiving a message from within an async context")]
+    AsyncContext,
+}
 
 impl From<crate::io::PortState> for RecvError {
-    fn from(_input: crate::io::PortState) -> Self {
-        Self
+    fn from(input: crate::io::PortState) -> Self {
+        use crate::io::PortState::*;
+        match input {
+            Unconnected => Self::Unconnected,
+            Connected => unreachable!(),
+            Disconnected => Self::Disconnected,
+            Closed => Self::Closed,
+        }
     }
 }
 
 #[cfg(feature = "flume")]
 impl From<flume::RecvError> for RecvError {
     fn from(_input: flume::RecvError) -> Self {
-        Self // TODO
+        Self::Disconnected
     }
 }
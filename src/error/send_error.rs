@@ -13,6 +13,15 @@ pub enum SendError {
 
     #[error("failed to send message on closed port")]
     Closed,
+
+    #[error("failed to send message on exhausted port")]
+    Exhausted,
+
+    #[error("timed out sending message")]
+    Timeout,
+
+    #[error("cannot block on sending a message from within an async context")]
+    AsyncContext,
 }
 
 impl From<crate::io::PortState> for SendError {
@@ -30,7 +39,7 @@ impl From<crate::io::PortState> for SendError {
 #[cfg(feature = "flume")]
 impl<T> From<flume::SendError<T>> for SendError {
     fn from(_input: flume::SendError<T>) -> Self {
-        Self // TODO
+        Self::Disconnected
     }
 }
 
@@ -40,3 +49,51 @@ impl<T> From<tokio::sync::mpsc::error::SendError<T>> for SendError {
         Self::Disconnected
     }
 }
+
+/// An error from sending a message, which hands the unsent message back.
+#[derive(Clone, Copy, Eq, Error, PartialEq)]
+#[error("{error}")]
+pub struct SendMessageError<T> {
+    error: SendError,
+    message: T,
+}
+
+impl<T> SendMessageError<T> {
+    pub fn new(error: SendError, message: T) -> Self {
+        Self { error, message }
+    }
+
+    /// Returns the reason the message couldn't be sent.
+    pub fn error(&self) -> SendError {
+        self.error
+    }
+
+    /// Returns the unsent message.
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    /// Returns the unsent message.
+    pub fn into_inner(self) -> T {
+        self.message
+    }
+
+    /// Returns the reason the message couldn't be sent, and the message.
+    pub fn into_parts(self) -> (SendError, T) {
+        (self.error, self.message)
+    }
+}
+
+impl<T> core::fmt::Debug for SendMessageError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SendMessageError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> From<SendMessageError<T>> for SendError {
+    fn from(input: SendMessageError<T>) -> Self {
+        input.error
+    }
+}
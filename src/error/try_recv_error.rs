@@ -3,19 +3,55 @@
 use thiserror::Error;
 
 #[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
-#[error("TryRecvError")]
-pub struct TryRecvError;
+pub enum TryRecvError {
+    #[error("no message available on empty port")]
+    Empty,
+
+    #[error("failed to receive message on unconnected port")]
+    Unconnected,
+
+    #[error("failed to receive message on disconnected port")]
+    Disconnected,
+
+    #[error("failed to receive message on closed port")]
+    Closed,
+
+    #[error("timed out receiving message")]
+    Timeout,
+
+    #[error("cannot block on receiving a message from within an async context")]
+    AsyncContext,
+}
+
+impl From<super::RecvError> for TryRecvError {
+    fn from(input: super::RecvError) -> Self {
+        use super::RecvError::*;
+        match input {
+            Unconnected => Self::Unconnected,
+            Disconnected => Self::Disconnected,
+            Closed => Self::Closed,
+            AsyncContext => Self::AsyncContext,
+        }
+    }
+}
 
 #[cfg(feature = "flume")]
 impl From<flume::TryRecvError> for TryRecvError {
-    fn from(_input: flume::TryRecvError) -> Self {
-        Self // TODO
+    fn from(input: flume::TryRecvError) -> Self {
+        match input {
+            flume::TryRecvError::Empty => Self::Empty,
+            flume::TryRecvError::Disconnected => Self::Disconnected,
+        }
     }
 }
 
 #[cfg(feature = "tokio")]
 impl From<tokio::sync::mpsc::error::TryRecvError> for TryRecvError {
-    fn from(_input: tokio::sync::mpsc::error::TryRecvError) -> Self {
-        Self // TODO
+    fn from(input: tokio::sync::mpsc::error::TryRecvError) -> Self {
+        use tokio::sync::mpsc::error::TryRecvError::*;
+        match input {
+            Empty => Self::Empty,
+            Disconnected => Self::Disconnected,
+        }
     }
 }
@@ -1,21 +1,132 @@
 // This is free and unencumbered software released into the public domain.
 
+use super::SendError;
 use thiserror::Error;
 
-#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
-#[error("TrySendError")]
-pub struct TrySendError;
+/// An error from sending a message without waiting, which hands the unsent
+/// message back.
+#[derive(Clone, Copy, Eq, Error, PartialEq)]
+pub enum TrySendError<T = ()> {
+    #[error("failed to send message on full port")]
+    Full(T),
+
+    #[error("failed to send message on unconnected port")]
+    Unconnected(T),
+
+    #[error("failed to send message on disconnected port")]
+    Disconnected(T),
+
+    #[error("failed to send message on closed port")]
+    Closed(T),
+
+    #[error("failed to send message on exhausted port")]
+    Exhausted(T),
+
+    #[error("timed out sending message")]
+    Timeout(T),
+
+    #[error("cannot block on sending a message from within an async context")]
+    AsyncContext(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Instantiates the error corresponding to the given send error.
+    pub(crate) fn new(error: SendError, message: T) -> Self {
+        use SendError::*;
+        match error {
+            Unconnected => Self::Unconnected(message),
+            Disconnected => Self::Disconnected(message),
+            Closed => Self::Closed(message),
+            Exhausted => Self::Exhausted(message),
+            Timeout => Self::Timeout(message),
+            AsyncContext => Self::AsyncContext(message),
+        }
+    }
+
+    /// Returns the unsent message.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(message)
+            | Self::Unconnected(message)
+            | Self::Disconnected(message)
+            | Self::Closed(message)
+            | Self::Exhausted(message)
+            | Self::Timeout(message)
+            | Self::AsyncContext(message) => message,
+        }
+    }
+
+    /// Maps the unsent message with the given function.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> TrySendError<U> {
+        match self {
+            Self::Full(message) => TrySendError::Full(f(message)),
+            Self::Unconnected(message) => TrySendError::Unconnected(f(message)),
+            Self::Disconnected(message) => TrySendError::Disconnected(f(message)),
+            Self::Closed(message) => TrySendError::Closed(f(message)),
+            Self::Exhausted(message) => TrySendError::Exhausted(f(message)),
+            Self::Timeout(message) => TrySendError::Timeout(f(message)),
+            Self::AsyncContext(message) => TrySendError::AsyncContext(f(message)),
+        }
+    }
+
+    /// Drops the unsent message.
+    pub fn without_message(self) -> TrySendError {
+        self.map(|_| ())
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Full(_) => "Full",
+            Self::Unconnected(_) => "Unconnected",
+            Self::Disconnected(_) => "Disconnected",
+            Self::Closed(_) => "Closed",
+            Self::Exhausted(_) => "Exhausted",
+            Self::Timeout(_) => "Timeout",
+            Self::AsyncContext(_) => "AsyncContext",
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}(..)", self.as_str())
+    }
+}
+
+impl From<SendError> for TrySendError {
+    fn from(input: SendError) -> Self {
+        Self::new(input, ())
+    }
+}
 
 #[cfg(feature = "flume")]
-impl<T> From<flume::TrySendError<T>> for TrySendError {
-    fn from(_input: flume::TrySendError<T>) -> Self {
-        Self // TODO
+impl<T> From<flume::TrySendError<T>> for TrySendError<T> {
+    fn from(input: flume::TrySendError<T>) -> Self {
+        match input {
+            flume::TrySendError::Full(message) => Self::Full(message),
+            flume::TrySendError::Disconnected(message) => Self::Disconnected(message),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> From<tokio::sync::mpsc::error::TrySendError<T>> for TrySendError<T> {
+    fn from(input: tokio::sync::mpsc::error::TrySendError<T>) -> Self {
+        use tokio::sync::mpsc::error::TrySendError::*;
+        match input {
+            Full(message) => Self::Full(message),
+            Closed(message) => Self::Disconnected(message),
+        }
     }
 }
 
 #[cfg(feature = "tokio")]
-impl<T> From<tokio::sync::mpsc::error::TrySendError<T>> for TrySendError {
-    fn from(_input: tokio::sync::mpsc::error::TrySendError<T>) -> Self {
-        Self // TODO
+impl<T> From<tokio::sync::mpsc::error::SendTimeoutError<T>> for TrySendError<T> {
+    fn from(input: tokio::sync::mpsc::error::SendTimeoutError<T>) -> Self {
+        use tokio::sync::mpsc::error::SendTimeoutError::*;
+        match input {
+            Timeout(message) => Self::Timeout(message),
+            Closed(message) => Self::Disconnected(message),
+        }
     }
 }
@@ -0,0 +1,17 @@
+// This is free and unencumbered software released into the public domain.
+
+//! A backend that runs on any executor, built on executor-neutral channels
+//! and spawning its blocks through a pluggable [`Spawner`](crate::Spawner).
+//!
+//! This requires the `std` feature, as its channels and timers do, so it
+//! serves executors such as `smol` or `async-std` but not `no_std` targets.
+
+pub use crate::flume::{
+    AbortHandle, Channel, Input, Inputs, ONESHOT, Output, Outputs, ShutdownHandle, UNLIMITED,
+};
+
+mod system;
+pub use system::*;
+
+mod task;
+pub(crate) use task::TaskSet;
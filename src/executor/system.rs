@@ -0,0 +1,54 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::TaskSet;
+use crate::{
+    error::Result,
+    io::Spawner,
+    model::{self, SystemDefinition},
+};
+use alloc::sync::Arc;
+
+pub type Subsystem = System;
+
+/// A system of blocks, spawned through a pluggable [`Spawner`] onto any
+/// executor.
+pub type System = model::System<TaskSet>;
+
+impl System {
+    /// Builds and executes a system, blocking until completion.
+    pub async fn run<S, F>(spawner: S, f: F) -> Result
+    where
+        S: Spawner + Send + Sync + 'static,
+        F: FnOnce(&mut Self),
+    {
+        Self::build(spawner, f).execute().await
+    }
+
+    /// Builds a new system, spawning its blocks through the given spawner.
+    pub fn build<S, F>(spawner: S, f: F) -> Self
+    where
+        S: Spawner + Send + Sync + 'static,
+        F: FnOnce(&mut Self),
+    {
+        let mut system = Self::new(spawner);
+        f(&mut system);
+        system
+    }
+
+    /// Instantiates a new system, spawning its blocks through the given
+    /// spawner.
+    pub fn new<S: Spawner + Send + Sync + 'static>(spawner: S) -> Self {
+        Self::with_tasks(TaskSet(crate::flume::TaskSet::new(Arc::new(spawner))))
+    }
+
+    /// Instantiates a new system from the given system definition, spawning
+    /// its blocks through the given spawner.
+    pub fn prepare<S>(system_definition: &SystemDefinition, spawner: S) -> Self
+    where
+        S: Spawner + Send + Sync + 'static,
+    {
+        let mut system = Self::new(spawner);
+        system_definition.wire(&mut system);
+        system
+    }
+}
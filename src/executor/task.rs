@@ -0,0 +1,58 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{AbortHandle, ShutdownHandle};
+use crate::{
+    PortEvent,
+    error::Result,
+    io::{FailurePolicy, SharedTimer},
+    model::{ErasedChannel, ErasedTask, PortType, Tasks},
+};
+use alloc::boxed::Box;
+use core::any::Any;
+
+/// A set of blocks, spawned through a [`Spawner`](crate::Spawner).
+///
+/// Unlike the Flume backend's, this has no default spawner, so a system of
+/// these must be given one.
+#[derive(Debug)]
+pub struct TaskSet(pub(crate) crate::flume::TaskSet);
+
+impl Tasks for TaskSet {
+    type Receiver<T: Send + 'static> = flume::Receiver<PortEvent<T>>;
+    type Sender<T: Send + 'static> = flume::Sender<PortEvent<T>>;
+    type AbortHandle = AbortHandle;
+    type ShutdownHandle = ShutdownHandle;
+
+    fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = Result> + Send + 'static,
+    {
+        self.0.spawn(task)
+    }
+
+    fn timer(&self) -> SharedTimer {
+        self.0.timer()
+    }
+
+    fn join_all(
+        &mut self,
+        policy: FailurePolicy,
+        shutdown: &ShutdownHandle,
+    ) -> impl Future<Output = Result> + Send {
+        self.0.join_all(policy, shutdown)
+    }
+
+    fn channel(port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel {
+        crate::flume::TaskSet::channel(port_type, buffer, senders)
+    }
+
+    fn forward(
+        port_type: PortType,
+        input: Box<dyn Any + Send>,
+        output: Box<dyn Any + Send>,
+        closed: Box<dyn Any + Send>,
+        shutdown: ShutdownHandle,
+    ) -> ErasedTask {
+        crate::flume::TaskSet::forward(port_type, input, output, closed, shutdown)
+    }
+}
@@ -0,0 +1,96 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Inputs, Outputs};
+use crate::{Connection, PortEvent};
+use alloc::{boxed::Box, vec::Vec};
+use core::any::TypeId;
+use flume::{Receiver, Sender};
+
+pub const UNLIMITED: isize = -1;
+pub const ONESHOT: isize = 1;
+
+#[derive(Debug, Default)]
+pub struct Channel<T, const N: isize = UNLIMITED> {
+    pub tx: Outputs<T, N>,
+    pub rx: Inputs<T, N>,
+}
+
+impl<T> Channel<T> {
+    pub fn pair() -> (Channel<T, UNLIMITED>, Channel<T, UNLIMITED>) {
+        (Self::bounded(1), Self::bounded(1))
+    }
+
+    /// Creates a one-shot connection.
+    ///
+    /// The output port disconnects after sending its first message, and the
+    /// input port after receiving it.
+    pub fn oneshot() -> Channel<T, ONESHOT> {
+        // Leave room for the disconnect event following the message:
+        Channel::from(flume::bounded(2))
+    }
+
+    /// Creates a bounded connection.
+    pub fn bounded(buffer: usize) -> Channel<T, UNLIMITED> {
+        Channel::from(flume::bounded(buffer))
+    }
+
+    /// Creates an unbounded connection.
+    pub fn unbounded() -> Channel<T, UNLIMITED> {
+        Channel::from(flume::unbounded())
+    }
+
+    /// Creates a bounded, type-erased connection merging the given number
+    /// of senders into one receiver.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn bounded_boxed(
+        buffer: usize,
+        senders: usize,
+    ) -> (
+        Vec<Box<dyn crate::io::OutputPort<T> + Send>>,
+        Box<dyn crate::io::InputPort<T> + Send>,
+    )
+    where
+        T: Send + 'static,
+    {
+        let (outputs, mut inputs) = Self::bounded(buffer).into_inner();
+        inputs.merge(senders.saturating_sub(1));
+        // Each sender is a distinct upstream connection, not a clone:
+        let others: Vec<_> = (1..senders)
+            .map(|_| Outputs::from(outputs.as_ref()))
+            .collect();
+        let outputs = core::iter::once(outputs)
+            .chain(others)
+            .map(|outputs| Box::new(outputs) as _)
+            .collect();
+        (outputs, Box::new(inputs))
+    }
+}
+
+impl<T: 'static, const N: isize> Channel<T, N> {
+    pub fn type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+}
+
+impl<T, const N: isize> Channel<T, N> {
+    pub fn into_inner(self) -> (Outputs<T, N>, Inputs<T, N>) {
+        (self.tx, self.rx)
+    }
+}
+
+impl<T, const N: isize> From<(Outputs<T, N>, Inputs<T, N>)> for Channel<T, N> {
+    fn from((tx, rx): (Outputs<T, N>, Inputs<T, N>)) -> Self {
+        Self { tx, rx }
+    }
+}
+
+impl<T, const N: isize> From<(Sender<PortEvent<T>>, Receiver<PortEvent<T>>)> for Channel<T, N> {
+    fn from((tx, rx): (Sender<PortEvent<T>>, Receiver<PortEvent<T>>)) -> Self {
+        Self {
+            tx: Outputs::<T, N>::from(tx),
+            rx: Inputs::<T, N>::from(rx),
+        }
+    }
+}
+
+impl<T: 'static> Connection<T> for Channel<T> {}
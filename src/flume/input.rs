@@ -0,0 +1,5 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Inputs;
+
+pub type Input<T> = Inputs<T, 1>;
@@ -1,91 +1,85 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::io::RecvError;
-use alloc::{borrow::Cow, boxed::Box};
-use dogma::{MaybeLabeled, MaybeNamed};
-use flume::Receiver;
-
-#[derive(Clone)]
-pub struct Inputs<T> {
-    pub(crate) rx: Receiver<T>,
-}
-
-impl<T> core::fmt::Debug for Inputs<T> {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Inputs").field("rx", &self.rx).finish()
-    }
-}
-
-impl<T> Inputs<T> {
-    pub fn capacity(&self) -> Option<usize> {
-        self.max_capacity().map(|max| max - self.rx.len())
-    }
-
-    pub fn max_capacity(&self) -> Option<usize> {
-        self.rx.capacity()
-    }
-
-    pub async fn recv(&mut self) -> Result<Option<T>, RecvError> {
-        match self.rx.recv_async().await {
-            Ok(value) => Ok(Some(value)),
-            Err(flume::RecvError::Disconnected) => Ok(None),
-        }
-    }
-}
-
-impl<T> AsRef<Receiver<T>> for Inputs<T> {
-    fn as_ref(&self) -> &Receiver<T> {
-        &self.rx
+use super::UNLIMITED;
+use crate::{
+    PortEvent,
+    error::TryRecvError,
+    io::{ChannelInputState, ChannelInputs, RawReceiver},
+};
+use core::time::Duration;
+use flume::{Receiver, TryRecvError as FlumeTryRecvError};
+
+pub type InputPortState<T> = ChannelInputState<Receiver<PortEvent<T>>>;
+
+/// An input port receiving at most `N` messages, unless unlimited, over a
+/// Flume channel.
+pub type Inputs<T, const N: isize = UNLIMITED> = ChannelInputs<Receiver<PortEvent<T>>, N>;
+
+impl<T> RawReceiver for Receiver<PortEvent<T>> {
+    type Message = T;
+
+    fn is_disconnected(&self) -> bool {
+        Receiver::is_disconnected(self)
     }
-}
 
-impl<T> AsMut<Receiver<T>> for Inputs<T> {
-    fn as_mut(&mut self) -> &mut Receiver<T> {
-        &mut self.rx
-    }
-}
+    /// Flume can't refuse further events, so a disconnected port instead
+    /// only drains the events already buffered.
+    fn close(&mut self) {}
 
-impl<T> From<Receiver<T>> for Inputs<T> {
-    fn from(input: Receiver<T>) -> Self {
-        Self { rx: input }
+    fn is_empty(&self) -> bool {
+        Receiver::is_empty(self)
     }
-}
 
-impl<T> From<&Receiver<T>> for Inputs<T> {
-    fn from(input: &Receiver<T>) -> Self {
-        Self { rx: input.clone() }
+    fn capacity(&self) -> Option<usize> {
+        Receiver::capacity(self).map(|max| max - self.len())
     }
-}
 
-#[async_trait::async_trait]
-impl<T: Send> crate::io::InputPort<T> for Inputs<T> {
-    fn is_empty(&self) -> bool {
-        self.rx.is_empty()
+    fn max_capacity(&self) -> Option<usize> {
+        Receiver::capacity(self)
     }
 
-    async fn recv(&mut self) -> Result<Option<T>, RecvError> {
-        self.recv().await
+    async fn recv(&mut self, drain: bool) -> Option<PortEvent<T>>
+    where
+        T: Send,
+    {
+        match drain {
+            false => self.recv_async().await.ok(),
+            true => Receiver::try_recv(self).ok(),
+        }
     }
-}
 
-impl<T> crate::io::Port<T> for Inputs<T> {
-    fn is_closed(&self) -> bool {
-        self.rx.is_disconnected()
+    fn try_recv(&mut self, drain: bool) -> Result<Option<PortEvent<T>>, TryRecvError> {
+        match Receiver::try_recv(self) {
+            Ok(event) => Ok(Some(event)),
+            Err(FlumeTryRecvError::Empty) if drain => Ok(None),
+            Err(FlumeTryRecvError::Disconnected) => Ok(None),
+            Err(error @ FlumeTryRecvError::Empty) => Err(error.into()),
+        }
     }
 
-    fn close(&mut self) {
-        // TODO
+    fn blocking_recv(&mut self, drain: bool) -> Option<PortEvent<T>> {
+        match drain {
+            false => Receiver::recv(self).ok(),
+            true => Receiver::try_recv(self).ok(),
+        }
     }
-}
 
-impl<T> MaybeLabeled for Inputs<T> {
-    fn label(&self) -> Option<Cow<'_, str>> {
-        None
+    async fn timeout<F: Future + Send>(duration: Duration, future: F) -> Option<F::Output>
+    where
+        F::Output: Send,
+    {
+        crate::io::timeout(duration, future).await
     }
-}
 
-impl<T> MaybeNamed for Inputs<T> {
-    fn name(&self) -> Option<Cow<'_, str>> {
-        None
+    #[cfg(feature = "stream")]
+    fn into_stream(self, drain: bool) -> crate::io::InputStream<PortEvent<T>>
+    where
+        T: Send + 'static,
+    {
+        use alloc::{boxed::Box, vec::Vec};
+        match drain {
+            false => Box::pin(Receiver::into_stream(self)),
+            true => Box::pin(tokio_stream::iter(self.drain().collect::<Vec<_>>())),
+        }
     }
 }
@@ -1,14 +1,35 @@
 // This is free and unencumbered software released into the public domain.
 
-pub fn bounded<T>(buffer: usize) -> (Outputs<T>, Inputs<T>) {
-    let (tx, rx) = flume::bounded(buffer);
-    let outputs = Outputs::from(tx);
-    let inputs = Inputs::from(rx);
-    (outputs, inputs)
-}
+mod channel;
+pub use channel::*;
+
+mod input;
+pub use input::*;
 
 mod inputs;
 pub use inputs::*;
 
+mod output;
+pub use output::*;
+
 mod outputs;
 pub use outputs::*;
+
+mod shutdown;
+pub use shutdown::*;
+
+mod stderr;
+pub use stderr::*;
+
+mod stdin;
+pub use stdin::*;
+
+mod stdout;
+pub use stdout::*;
+
+mod system;
+pub use system::*;
+
+mod task;
+pub use task::AbortHandle;
+pub(crate) use task::TaskSet;
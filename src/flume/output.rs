@@ -0,0 +1,5 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Outputs;
+
+pub type Output<T> = Outputs<T, 1>;
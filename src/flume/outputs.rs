@@ -1,84 +1,77 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::io::SendError;
-use alloc::{borrow::Cow, boxed::Box};
-use dogma::{MaybeLabeled, MaybeNamed};
+use super::UNLIMITED;
+use crate::{
+    PortEvent,
+    error::TrySendError,
+    io::{ChannelOutputState, ChannelOutputs, RawSender},
+};
+use core::time::Duration;
 use flume::Sender;
 
-#[derive(Clone)]
-pub struct Outputs<T> {
-    pub(crate) tx: Sender<T>,
-}
+/// How long [`Outputs::send_timeout`] waits before retrying to send on a
+/// full channel.
+const RETRY_INTERVAL: Duration = Duration::from_millis(1);
 
-impl<T> core::fmt::Debug for Outputs<T> {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Outputs").field("tx", &self.tx).finish()
-    }
-}
+pub type OutputPortState<T> = ChannelOutputState<Sender<PortEvent<T>>>;
 
-impl<T> Outputs<T> {
-    pub fn capacity(&self) -> Option<usize> {
-        self.max_capacity().map(|max| max - self.tx.len())
-    }
+/// An output port sending at most `N` messages, unless unlimited, over a
+/// Flume channel.
+pub type Outputs<T, const N: isize = UNLIMITED> = ChannelOutputs<Sender<PortEvent<T>>, N>;
 
-    pub fn max_capacity(&self) -> Option<usize> {
-        self.tx.capacity()
-    }
+impl<T> RawSender for Sender<PortEvent<T>> {
+    type Message = T;
 
-    pub async fn send(&self, value: T) -> Result<(), SendError> {
-        Ok(self.tx.send_async(value).await?)
+    fn is_disconnected(&self) -> bool {
+        Sender::is_disconnected(self)
     }
-}
 
-impl<T> AsRef<Sender<T>> for Outputs<T> {
-    fn as_ref(&self) -> &Sender<T> {
-        &self.tx
+    fn capacity(&self) -> Option<usize> {
+        Sender::capacity(self).map(|max| max - self.len())
     }
-}
 
-impl<T> AsMut<Sender<T>> for Outputs<T> {
-    fn as_mut(&mut self) -> &mut Sender<T> {
-        &mut self.tx
+    fn max_capacity(&self) -> Option<usize> {
+        Sender::capacity(self)
     }
-}
 
-impl<T> From<Sender<T>> for Outputs<T> {
-    fn from(input: Sender<T>) -> Self {
-        Self { tx: input }
+    async fn send(&self, event: PortEvent<T>) -> Result<(), PortEvent<T>>
+    where
+        T: Send,
+    {
+        self.send_async(event).await.map_err(|error| error.0)
     }
-}
 
-impl<T> From<&Sender<T>> for Outputs<T> {
-    fn from(input: &Sender<T>) -> Self {
-        Self { tx: input.clone() }
+    async fn send_timeout(
+        &self,
+        event: PortEvent<T>,
+        timeout: Duration,
+    ) -> Result<(), TrySendError<PortEvent<T>>>
+    where
+        T: Send,
+    {
+        // Flume can't wait for buffer capacity without taking the event,
+        // which then couldn't be handed back on timeout, so this retries:
+        let mut event = Some(event);
+        let sent = crate::io::timeout(timeout, async {
+            loop {
+                match Sender::try_send(self, event.take().unwrap()) {
+                    Ok(()) => return Ok(()),
+                    Err(flume::TrySendError::Full(full)) => event = Some(full),
+                    Err(error) => return Err(error.into()),
+                }
+                crate::io::sleep(RETRY_INTERVAL).await;
+            }
+        })
+        .await;
+        // A timeout only elapses while waiting to retry, with the event back:
+        sent.unwrap_or_else(|| Err(TrySendError::Timeout(event.take().unwrap())))
     }
-}
 
-#[async_trait::async_trait]
-impl<T: Send + 'static> crate::io::OutputPort<T> for Outputs<T> {
-    async fn send(&self, value: T) -> Result<(), SendError> {
-        self.send(value).await
+    fn try_send(&self, event: PortEvent<T>) -> Result<(), TrySendError<PortEvent<T>>> {
+        Sender::try_send(self, event).map_err(TrySendError::from)
     }
-}
-
-impl<T> crate::io::Port<T> for Outputs<T> {
-    fn is_closed(&self) -> bool {
-        self.tx.is_disconnected()
-    }
-
-    fn close(&mut self) {
-        drop(self.tx.downgrade())
-    }
-}
-
-impl<T> MaybeLabeled for Outputs<T> {
-    fn label(&self) -> Option<Cow<'_, str>> {
-        None
-    }
-}
 
-impl<T> MaybeNamed for Outputs<T> {
-    fn name(&self) -> Option<Cow<'_, str>> {
-        None
+    fn blocking_send(&self, event: PortEvent<T>) -> Result<(), PortEvent<T>> {
+        Sender::send(self, event).map_err(|error| error.0)
     }
 }
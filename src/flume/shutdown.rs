@@ -0,0 +1,95 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::Result,
+    io::{Either, InputPort, OutputPort, select},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::time::Duration;
+use flume::{Receiver, Sender};
+use std::sync::Mutex;
+
+/// A handle for shutting down a running system.
+///
+/// Shutting down closes the system's exported input ports, lets its blocks
+/// drain their in-flight messages within a grace period, and then aborts
+/// any stragglers.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle(Arc<ShutdownState>);
+
+#[derive(Debug)]
+struct ShutdownState {
+    grace_period: Mutex<Option<Duration>>,
+    /// Dropped upon shutdown, which wakes up all receivers at once.
+    tx: Mutex<Option<Sender<()>>>,
+    rx: Receiver<()>,
+}
+
+impl Default for ShutdownHandle {
+    fn default() -> Self {
+        let (tx, rx) = flume::bounded(0);
+        Self(Arc::new(ShutdownState {
+            grace_period: Mutex::new(None),
+            tx: Mutex::new(Some(tx)),
+            rx,
+        }))
+    }
+}
+
+impl ShutdownHandle {
+    /// Requests the shutdown of the system, allowing its blocks the given
+    /// grace period to complete before they are aborted.
+    ///
+    /// Only the first request has any effect.
+    pub fn shutdown(&self, grace_period: Duration) {
+        let mut state = self.0.grace_period.lock().unwrap();
+        if state.is_none() {
+            *state = Some(grace_period);
+            self.0.tx.lock().unwrap().take();
+        }
+    }
+
+    /// Checks whether the shutdown of the system has been requested.
+    pub fn is_shutdown(&self) -> bool {
+        self.0.grace_period.lock().unwrap().is_some()
+    }
+
+    /// Waits until the shutdown of the system is requested, returning the
+    /// grace period.
+    pub(crate) fn requested(&self) -> impl Future<Output = Duration> + Send + 'static {
+        let state = Arc::clone(&self.0);
+        async move {
+            let _ = state.rx.recv_async().await; // only ever disconnected
+            let grace_period = *state.grace_period.lock().unwrap();
+            grace_period.unwrap_or_default()
+        }
+    }
+}
+
+/// Forwards messages from the given input port to the given output port
+/// until either port is disconnected, the given `closed` port signals that
+/// the receiving end was closed, or the shutdown is requested.
+pub(crate) async fn forward_until_shutdown<T: Send + 'static>(
+    mut input: Box<dyn InputPort<T> + Send>,
+    output: Arc<dyn OutputPort<T> + Send>,
+    mut closed: Box<dyn InputPort<()> + Send>,
+    shutdown: ShutdownHandle,
+) -> Result {
+    let mut requested = Box::pin(shutdown.requested());
+    loop {
+        let stopped = select(closed.recv(), &mut requested);
+        let message = match select(input.recv(), stopped).await {
+            Either::Left(message) => message?,
+            Either::Right(_) => None,
+        };
+        // Stop once the shutdown is requested or either side disconnects:
+        let sent = match message {
+            Some(message) => output.send(message).await.is_ok(),
+            None => false,
+        };
+        if !sent {
+            input.close();
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Outputs;
+use crate::error::{Error, Result};
+use alloc::string::String;
+use core::str::FromStr;
+
+/// Parses lines read from the standard input and sends them downstream.
+///
+/// Reading blocks the current thread, which is the block's own thread when
+/// spawned on a [`System`](super::System).
+pub async fn stdin<T: FromStr + Send>(outputs: Outputs<T>) -> Result {
+    use std::io::ErrorKind;
+
+    let input = std::io::stdin();
+    let mut line = String::new();
+
+    while input.read_line(&mut line)? > 0 {
+        let output = line
+            .trim_end_matches(['\n', '\r'])
+            .parse()
+            .map_err(|_| Error::Stdio(ErrorKind::InvalidInput.into()))?;
+        line.clear();
+        outputs.send(output).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Inputs;
+use crate::error::Result;
+use alloc::string::ToString;
+
+/// Writes the messages received from upstream to the standard output as
+/// lines.
+///
+/// Writing blocks the current thread, which is the block's own thread when
+/// spawned on a [`System`](super::System).
+pub async fn stdout<T: ToString + Send>(mut inputs: Inputs<T>) -> Result {
+    use std::io::Write;
+
+    let mut output = std::io::stdout();
+
+    while let Some(input) = inputs.recv().await? {
+        let mut line = input.to_string();
+        if !line.ends_with('\n') {
+            line.push('\n');
+        }
+        output.write_all(line.as_bytes())?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Inputs, Outputs, TaskSet};
+use crate::{error::Result, model};
+
+pub type Subsystem = System;
+
+/// A system of blocks, each running on its own thread.
+///
+/// This doesn't depend on any particular async runtime: [`System::execute`]
+/// can be awaited on any executor, or on none at all via
+/// [`System::blocking_execute`].
+pub type System = model::System<TaskSet>;
+
+impl System {
+    /// Waits for all blocks of this system to complete, blocking the
+    /// current thread.
+    ///
+    /// See [`System::execute`].
+    pub fn blocking_execute(self) -> Result {
+        super::task::block_on(self.execute())
+    }
+
+    pub fn read_stdin<T>(&mut self) -> Inputs<T>
+    where
+        T: core::str::FromStr + Send + 'static,
+        <T as core::str::FromStr>::Err: Send,
+    {
+        let (output, input) = super::Channel::<T>::bounded(1).into_inner();
+        self.spawn(super::stdin(output));
+        input
+    }
+
+    pub fn write_stdout<T>(&mut self) -> Outputs<T>
+    where
+        T: alloc::string::ToString + Send + 'static,
+    {
+        let (output, input) = super::Channel::<T>::bounded(1).into_inner();
+        self.spawn(super::stdout(input));
+        output
+    }
+}
@@ -0,0 +1,286 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::ShutdownHandle;
+use crate::{
+    PortEvent,
+    error::{Error, Result},
+    io::{
+        Either, FailurePolicy, Failures, SharedTimer, Spawner, Task, ThreadTimer, TimerContext,
+        select,
+    },
+    model::{ErasedChannel, ErasedTask, PortType, Tasks},
+};
+use alloc::{boxed::Box, string::String, sync::Arc, task::Wake, vec::Vec};
+use core::{
+    pin::{Pin, pin},
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll, Waker},
+};
+use flume::{Receiver, Sender};
+use std::{
+    sync::Mutex,
+    thread::{self, Thread},
+};
+
+/// A handle for aborting a block spawned on a [`System`](super::System).
+///
+/// An aborted block stops the next time it yields; a block stuck in
+/// blocking code is only stopped once that returns.
+#[derive(Clone, Debug, Default)]
+pub struct AbortHandle(Arc<AbortState>);
+
+#[derive(Debug, Default)]
+struct AbortState {
+    aborted: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl AbortHandle {
+    /// Aborts the block.
+    pub fn abort(&self) {
+        self.0.aborted.store(true, Ordering::Release);
+        if let Some(waker) = self.0.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// Checks whether the block has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.0.aborted.load(Ordering::Acquire)
+    }
+}
+
+/// A task that completes early once aborted, and that reports a panic as
+/// an error.
+struct Abortable<F> {
+    task: Pin<Box<F>>,
+    handle: AbortHandle,
+}
+
+impl<F: Future<Output = Result>> Future for Abortable<F> {
+    type Output = Option<Result>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        *self.handle.0.waker.lock().unwrap() = Some(cx.waker().clone());
+        if self.handle.is_aborted() {
+            return Poll::Ready(None);
+        }
+        let task = self.task.as_mut();
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task.poll(cx))) {
+            Ok(Poll::Ready(result)) => Poll::Ready(Some(result)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Some(Err(panicked(panic)))),
+        }
+    }
+}
+
+/// A set of blocks, spawned through a [`Spawner`], by default each on its
+/// own thread.
+pub struct TaskSet {
+    spawner: Arc<dyn Spawner + Send + Sync>,
+    timer: SharedTimer,
+    handles: Vec<AbortHandle>,
+    /// The results of completed tasks, or `None` for aborted ones.
+    results: (Sender<Option<Result>>, Receiver<Option<Result>>),
+    pending: usize,
+}
+
+impl Default for TaskSet {
+    fn default() -> Self {
+        Self::new(Arc::new(|task: Task| {
+            thread::spawn(move || block_on(task));
+        }))
+    }
+}
+
+impl core::fmt::Debug for TaskSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TaskSet")
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+impl TaskSet {
+    pub(crate) fn new(spawner: Arc<dyn Spawner + Send + Sync>) -> Self {
+        let timer = spawner.timer().unwrap_or_else(|| Arc::new(ThreadTimer));
+        Self {
+            spawner,
+            timer,
+            handles: Vec::new(),
+            results: flume::unbounded(),
+            pending: 0,
+        }
+    }
+
+    /// Returns the timer of the spawner, or else a real-time one.
+    pub(crate) fn timer(&self) -> SharedTimer {
+        Arc::clone(&self.timer)
+    }
+
+    pub(crate) fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = Result> + Send + 'static,
+    {
+        let handle = AbortHandle::default();
+        let task = Abortable {
+            task: Box::pin(crate::io::AsyncContext::new(TimerContext::new(
+                task,
+                self.timer(),
+            ))),
+            handle: handle.clone(),
+        };
+        let results = self.results.0.clone();
+        self.spawner.spawn(Box::pin(async move {
+            let _ = results.send(task.await);
+        }));
+        self.handles.push(handle.clone());
+        self.pending += 1;
+        handle
+    }
+
+    /// Waits for the next task to complete, skipping aborted ones.
+    ///
+    /// Returns `None` once all tasks have completed.
+    pub(crate) async fn join_next(&mut self) -> Option<Result> {
+        while self.pending > 0 {
+            let result = self.results.1.recv_async().await.ok()?;
+            self.pending -= 1;
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+
+    /// Waits for all tasks to complete, collecting their errors according
+    /// to the given failure policy.
+    ///
+    /// Once shutdown is requested, the tasks are given its grace period to
+    /// complete, after which any remaining ones are aborted.
+    pub(crate) async fn join_all(
+        &mut self,
+        policy: FailurePolicy,
+        shutdown: &ShutdownHandle,
+    ) -> Result {
+        let mut failures = Failures::new(policy);
+        let mut requested = pin!(shutdown.requested());
+        let mut grace_period = None;
+        loop {
+            let result = match &mut grace_period {
+                None => match select(self.join_next(), &mut requested).await {
+                    Either::Left(result) => result,
+                    Either::Right(duration) => {
+                        grace_period = Some(self.timer.sleep(duration));
+                        continue;
+                    },
+                },
+                Some(grace_period) => match select(self.join_next(), grace_period).await {
+                    Either::Left(result) => result,
+                    Either::Right(()) => {
+                        self.shutdown(); // abort the stragglers
+                        None
+                    },
+                },
+            };
+            let Some(result) = result else {
+                break;
+            };
+            let Err(error) = result else {
+                continue;
+            };
+            if let Some(error) = failures.record(error) {
+                self.shutdown();
+                return Err(error);
+            }
+        }
+        failures.into_result()
+    }
+
+    /// Aborts all tasks.
+    pub(crate) fn shutdown(&mut self) {
+        for handle in self.handles.drain(..) {
+            handle.abort();
+        }
+        self.pending = 0;
+    }
+}
+
+impl Tasks for TaskSet {
+    type Receiver<T: Send + 'static> = flume::Receiver<PortEvent<T>>;
+    type Sender<T: Send + 'static> = flume::Sender<PortEvent<T>>;
+    type AbortHandle = AbortHandle;
+    type ShutdownHandle = ShutdownHandle;
+
+    fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = Result> + Send + 'static,
+    {
+        TaskSet::spawn(self, task)
+    }
+
+    fn timer(&self) -> SharedTimer {
+        TaskSet::timer(self)
+    }
+
+    fn join_all(
+        &mut self,
+        policy: FailurePolicy,
+        shutdown: &ShutdownHandle,
+    ) -> impl Future<Output = Result> + Send {
+        TaskSet::join_all(self, policy, shutdown)
+    }
+
+    fn channel(port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel {
+        (port_type.flume_channel)(buffer, senders)
+    }
+
+    fn forward(
+        port_type: PortType,
+        input: Box<dyn core::any::Any + Send>,
+        output: Box<dyn core::any::Any + Send>,
+        closed: Box<dyn core::any::Any + Send>,
+        shutdown: ShutdownHandle,
+    ) -> ErasedTask {
+        (port_type.flume_forward)(input, output, closed, shutdown)
+    }
+}
+
+impl Drop for TaskSet {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Runs the given future to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+/// Converts the payload of a panicking block into an error.
+pub(crate) fn panicked(panic: Box<dyn core::any::Any + Send>) -> Error {
+    let message = match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&'static str>() {
+            Ok(message) => String::from(*message),
+            Err(_) => String::from("block panicked"),
+        },
+    };
+    Error::Other(message.into())
+}
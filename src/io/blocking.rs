@@ -0,0 +1,59 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use core::{
+    cell::Cell,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static ASYNC_CONTEXT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Checks whether the current thread is running a task spawned on a system,
+/// where blocking on a port would stall the executor.
+///
+/// Any other thread is free to block, be it a plain thread, one of Tokio's
+/// `spawn_blocking` threads, or one that entered a runtime. Elsewhere on a
+/// Tokio worker thread, Tokio's own blocking operations panic instead.
+///
+/// Without the `std` feature, this always returns `false`.
+pub(crate) fn is_async_context() -> bool {
+    #[cfg(feature = "std")]
+    let result = ASYNC_CONTEXT.get();
+    #[cfg(not(feature = "std"))]
+    let result = false;
+    result
+}
+
+/// A future marking the thread it's polled on as an async context.
+#[cfg(feature = "std")]
+pub(crate) struct AsyncContext<F>(Pin<Box<F>>);
+
+#[cfg(feature = "std")]
+impl<F> AsyncContext<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self(Box::pin(future))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Future> Future for AsyncContext<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        /// Restores the outer context, even if the future panics.
+        struct Restore(bool);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                ASYNC_CONTEXT.set(self.0);
+            }
+        }
+        let _restore = Restore(ASYNC_CONTEXT.replace(true));
+        self.0.as_mut().poll(cx)
+    }
+}
@@ -0,0 +1,514 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::RawReceiver;
+use crate::{
+    PortDirection, PortEvent, PortState,
+    error::{RecvError, TryRecvError},
+};
+use alloc::{borrow::Cow, boxed::Box};
+use core::{any::TypeId, time::Duration};
+use dogma::{MaybeLabeled, MaybeNamed};
+
+/// The state of an input port, with the receiving end `R` of its channel.
+#[derive(Default)]
+pub enum ChannelInputState<R> {
+    #[default]
+    Unconnected,
+    Connected(R),
+    Disconnected(R),
+    Closed,
+}
+
+impl<R> core::fmt::Debug for ChannelInputState<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use ChannelInputState::*;
+        match self {
+            Unconnected => f.write_str("Unconnected"),
+            Connected(_) => f.write_str("Connected"),
+            Disconnected(_) => f.write_str("Disconnected"),
+            Closed => f.write_str("Closed"),
+        }
+    }
+}
+
+impl<R: RawReceiver> From<&ChannelInputState<R>> for RecvError {
+    fn from(input: &ChannelInputState<R>) -> Self {
+        PortState::from(input).into()
+    }
+}
+
+impl<R: RawReceiver> From<&ChannelInputState<R>> for PortState {
+    fn from(input: &ChannelInputState<R>) -> Self {
+        use ChannelInputState::*;
+        match input {
+            Unconnected => PortState::Unconnected,
+            Connected(rx) => {
+                if rx.is_disconnected() {
+                    PortState::Disconnected
+                } else {
+                    PortState::Connected
+                }
+            },
+            Disconnected(_) => PortState::Disconnected,
+            Closed => PortState::Closed,
+        }
+    }
+}
+
+/// An input port receiving at most `N` messages, unless unlimited, on the
+/// receiving end `R` of a backend's channel.
+pub struct ChannelInputs<R, const N: isize> {
+    pub(crate) state: ChannelInputState<R>,
+    /// The number of upstream output ports that haven't yet disconnected.
+    pub(crate) senders: usize,
+    /// The number of connect events yet to be delivered for the upstream
+    /// output ports wired to this port.
+    pub(crate) connects: usize,
+    /// The number of messages received so far, bounded by `N` unless
+    /// unlimited.
+    pub(crate) received: usize,
+    /// The name of this port, if any.
+    pub(crate) name: Option<&'static str>,
+    /// The human-readable label of this port, if any.
+    pub(crate) label: Option<&'static str>,
+}
+
+impl<R, const N: isize> Default for ChannelInputs<R, N> {
+    fn default() -> Self {
+        Self {
+            state: ChannelInputState::default(),
+            senders: 0,
+            connects: 0,
+            received: 0,
+            name: None,
+            label: None,
+        }
+    }
+}
+
+impl<R: RawReceiver, const N: isize> ChannelInputs<R, N>
+where
+    R::Message: 'static,
+{
+    pub fn type_id(&self) -> TypeId {
+        TypeId::of::<R::Message>()
+    }
+}
+
+impl<R, const N: isize> core::fmt::Debug for ChannelInputs<R, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut f = f.debug_tuple("Inputs");
+        if let Some(name) = self.name {
+            f.field(&name);
+        }
+        f.field(&self.state).finish()
+    }
+}
+
+impl<R: RawReceiver, const N: isize> ChannelInputs<R, N> {
+    /// Sets the name of this port.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the human-readable label of this port.
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn close(&mut self) {
+        use ChannelInputState::*;
+        match self.state {
+            Unconnected | Disconnected(_) => self.state = Closed,
+            Connected(ref mut rx) => {
+                rx.close();
+                self.state = Closed;
+            },
+            Closed => (), // idempotent
+        }
+        self.senders = 0;
+        self.connects = 0;
+    }
+
+    /// Merges the given number of additional upstream output ports into
+    /// this port, which will only disconnect once all of them have.
+    pub(crate) fn merge(&mut self, senders: usize) {
+        self.senders += senders;
+        self.connects += senders;
+    }
+
+    /// Records the disconnection of one upstream output port, returning
+    /// whether this port is now disconnected as a whole.
+    fn disconnect_sender(&mut self) -> bool {
+        self.senders = self.senders.saturating_sub(1);
+        if self.senders > 0 {
+            return false;
+        }
+        self.disconnect();
+        true
+    }
+
+    /// Checks whether this port has received the maximum number of messages.
+    pub fn is_exhausted(&self) -> bool {
+        N >= 0 && self.received >= N as usize
+    }
+
+    /// Counts a received message, disconnecting this port once it has
+    /// received the maximum number of messages.
+    fn count_message(&mut self) {
+        self.received += 1;
+        if self.is_exhausted() {
+            self.disconnect();
+        }
+    }
+
+    /// Accounts for a received event, returning the event to deliver, or
+    /// `None` if it should be skipped.
+    ///
+    /// Upstream output ports announce their (re)connection and
+    /// disconnection with events, but those that were dropped without
+    /// announcing it are accounted for once the channel is torn down.
+    #[allow(clippy::type_complexity)]
+    fn accept(
+        &mut self,
+        event: Option<PortEvent<R::Message>>,
+    ) -> Option<Option<PortEvent<R::Message>>> {
+        use PortEvent::*;
+        match event {
+            Some(Message(_)) if self.is_exhausted() => None, // discard
+            Some(Message(message)) => {
+                self.count_message();
+                Some(Some(Message(message)))
+            },
+            Some(Connect) => {
+                self.senders += 1;
+                Some(Some(Connect))
+            },
+            Some(Failure) => Some(Some(Failure)),
+            Some(Disconnect) | None if self.senders > 0 => {
+                self.disconnect_sender();
+                Some(Some(Disconnect))
+            },
+            Some(Disconnect) => None, // already accounted for
+            None => Some(None),
+        }
+    }
+
+    /// Disconnects this port, after which it only drains the messages that
+    /// were already buffered.
+    pub fn disconnect(&mut self) {
+        use ChannelInputState::*;
+        replace_with::replace_with_or_abort(&mut self.state, |self_| match self_ {
+            Unconnected => Unconnected,
+            Connected(mut rx) => {
+                rx.close();
+                Disconnected(rx)
+            },
+            Disconnected(rx) => Disconnected(rx),
+            Closed => Closed,
+        })
+    }
+
+    /// Returns the receiving end of this port's channel, if any, and whether
+    /// it's only drained.
+    fn receiver(&mut self) -> Option<(&mut R, bool)> {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref mut rx) => Some((rx, false)),
+            Disconnected(ref mut rx) => Some((rx, true)),
+            _ => None,
+        }
+    }
+
+    pub fn direction(&self) -> PortDirection {
+        PortDirection::Input
+    }
+
+    pub fn state(&self) -> PortState {
+        (&self.state).into()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref rx) | Disconnected(ref rx) => rx.is_empty(),
+            _ => true,
+        }
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref rx) | Disconnected(ref rx) => rx.capacity(),
+            _ => None,
+        }
+    }
+
+    pub fn max_capacity(&self) -> Option<usize> {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref rx) | Disconnected(ref rx) => rx.max_capacity(),
+            _ => None,
+        }
+    }
+
+    pub async fn recv(&mut self) -> Result<Option<R::Message>, RecvError>
+    where
+        R::Message: Send,
+    {
+        loop {
+            return match self.recv_event().await? {
+                Some(PortEvent::Message(m)) => Ok(Some(m)),
+                Some(PortEvent::Connect | PortEvent::Failure | PortEvent::Disconnect) => continue,
+                None => Ok(None),
+            };
+        }
+    }
+
+    /// Receives the next port event, waiting until one is available.
+    ///
+    /// A connect event is delivered for each upstream output port wired to
+    /// this port, as well as whenever one reconnects, a disconnect event
+    /// whenever one disconnects, and a failure event whenever the block
+    /// upstream of one fails.
+    /// Returns `Ok(None)` once all of them have disconnected and the port
+    /// has been drained.
+    pub async fn recv_event(&mut self) -> Result<Option<PortEvent<R::Message>>, RecvError>
+    where
+        R::Message: Send,
+    {
+        loop {
+            if self.connects > 0 {
+                self.connects -= 1;
+                return Ok(Some(PortEvent::Connect));
+            }
+            let event = match self.receiver() {
+                Some((rx, drain)) => rx.recv(drain).await,
+                None => None,
+            };
+            if let Some(event) = self.accept(event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub async fn recv_deadline(
+        &mut self,
+        deadline: std::time::Instant,
+    ) -> Result<Option<R::Message>, TryRecvError>
+    where
+        R: Send,
+        R::Message: Send,
+    {
+        self.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+            .await
+    }
+
+    pub async fn recv_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<R::Message>, TryRecvError>
+    where
+        R: Send,
+        R::Message: Send,
+    {
+        match R::timeout(timeout, self.recv()).await {
+            Some(result) => Ok(result?),
+            None => Err(TryRecvError::Timeout),
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<Option<R::Message>, TryRecvError> {
+        loop {
+            let Some((rx, drain)) = self.receiver() else {
+                return Ok(None);
+            };
+            return match rx.try_recv(drain)? {
+                Some(PortEvent::Message(_)) if self.is_exhausted() => continue, // discard
+                Some(PortEvent::Message(m)) => {
+                    self.count_message();
+                    Ok(Some(m))
+                },
+                Some(PortEvent::Connect) => {
+                    self.senders += 1;
+                    continue;
+                },
+                Some(PortEvent::Failure) => continue,
+                Some(PortEvent::Disconnect) if self.senders == 0 => continue,
+                Some(PortEvent::Disconnect) if !self.disconnect_sender() => continue,
+                Some(PortEvent::Disconnect) | None => Ok(None),
+            };
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    pub fn into_stream(self) -> crate::io::InputStream<R::Message>
+    where
+        R::Message: Send + 'static,
+    {
+        use ChannelInputState::*;
+        use tokio_stream::StreamExt;
+        let mut senders = self.senders;
+        let remaining = match N {
+            ..0 => usize::MAX,
+            max => (max as usize).saturating_sub(self.received),
+        };
+        let events = match self.state {
+            Connected(rx) => rx.into_stream(false),
+            Disconnected(rx) => rx.into_stream(true),
+            _ => return Box::pin(tokio_stream::empty()),
+        };
+        Box::pin(
+            events
+                .map_while(move |event| match event {
+                    PortEvent::Connect => {
+                        senders += 1;
+                        Some(None)
+                    },
+                    PortEvent::Disconnect => {
+                        senders = senders.saturating_sub(1);
+                        (senders > 0).then_some(None)
+                    },
+                    event => Some(event.into_message()),
+                })
+                .filter_map(core::convert::identity)
+                .take(remaining),
+        )
+    }
+
+    /// Receives the next message from synchronous code, blocking the
+    /// current thread until one is available.
+    ///
+    /// Returns `Ok(None)` once the port has been disconnected and drained,
+    /// and an error if called from within a system's task.
+    ///
+    /// # Panics
+    ///
+    /// With the Tokio backend, panics if called elsewhere on an async Tokio
+    /// worker thread, as Tokio's own blocking operations do.
+    pub fn blocking_recv(&mut self) -> Result<Option<R::Message>, RecvError> {
+        if crate::io::is_async_context() {
+            return Err(RecvError::AsyncContext);
+        }
+        loop {
+            let event = match self.receiver() {
+                Some((rx, drain)) => rx.blocking_recv(drain),
+                None => None,
+            };
+            return match self.accept(event) {
+                Some(Some(PortEvent::Message(m))) => Ok(Some(m)),
+                Some(Some(PortEvent::Connect | PortEvent::Failure | PortEvent::Disconnect))
+                | None => {
+                    continue;
+                },
+                Some(None) => Ok(None),
+            };
+        }
+    }
+}
+
+impl<R, const N: isize> AsRef<R> for ChannelInputs<R, N> {
+    fn as_ref(&self) -> &R {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref rx) | Disconnected(ref rx) => rx,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<R, const N: isize> AsMut<R> for ChannelInputs<R, N> {
+    fn as_mut(&mut self) -> &mut R {
+        use ChannelInputState::*;
+        match self.state {
+            Connected(ref mut rx) | Disconnected(ref mut rx) => rx,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<R: RawReceiver, const N: isize> From<R> for ChannelInputs<R, N> {
+    fn from(input: R) -> Self {
+        use ChannelInputState::*;
+        Self {
+            state: if input.is_disconnected() {
+                Disconnected(input)
+            } else {
+                Connected(input)
+            },
+            senders: 1,
+            connects: 1,
+            received: 0,
+            name: None,
+            label: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<R, const N: isize> crate::io::InputPort<R::Message> for ChannelInputs<R, N>
+where
+    R: RawReceiver + Send,
+    R::Message: Send + 'static,
+{
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    async fn recv(&mut self) -> Result<Option<R::Message>, RecvError> {
+        self.recv().await
+    }
+
+    async fn recv_event(&mut self) -> Result<Option<PortEvent<R::Message>>, RecvError> {
+        self.recv_event().await
+    }
+
+    async fn recv_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<R::Message>, TryRecvError> {
+        self.recv_timeout(timeout).await
+    }
+
+    fn try_recv(&mut self) -> Result<Option<R::Message>, TryRecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "stream")]
+    fn into_stream(self: Box<Self>) -> crate::io::InputStream<R::Message> {
+        (*self).into_stream()
+    }
+}
+
+impl<R, const N: isize> crate::io::Port<R::Message> for ChannelInputs<R, N>
+where
+    R: RawReceiver,
+    R::Message: Send,
+{
+    fn close(&mut self) {
+        self.close()
+    }
+
+    fn direction(&self) -> PortDirection {
+        self.direction()
+    }
+
+    fn state(&self) -> PortState {
+        self.state()
+    }
+}
+
+impl<R, const N: isize> MaybeNamed for ChannelInputs<R, N> {
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.name.map(Cow::Borrowed)
+    }
+}
+
+impl<R, const N: isize> MaybeLabeled for ChannelInputs<R, N> {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        self.label.map(Cow::Borrowed)
+    }
+}
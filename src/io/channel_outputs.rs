@@ -0,0 +1,489 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{MessageLimit, RawSender};
+use crate::{
+    PortDirection, PortEvent, PortState,
+    error::{SendError, SendMessageError, TrySendError},
+};
+use alloc::{borrow::Cow, boxed::Box, sync::Arc};
+use core::{
+    any::TypeId,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use dogma::{MaybeLabeled, MaybeNamed};
+
+/// The state of an output port, with the sending end `S` of its channel.
+#[derive(Clone, Default)]
+pub enum ChannelOutputState<S> {
+    #[default]
+    Unconnected,
+    Connected(S),
+    Disconnected,
+    Closed,
+}
+
+impl<S> core::fmt::Debug for ChannelOutputState<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use ChannelOutputState::*;
+        match self {
+            Unconnected => f.write_str("Unconnected"),
+            Connected(_) => f.write_str("Connected"),
+            Disconnected => f.write_str("Disconnected"),
+            Closed => f.write_str("Closed"),
+        }
+    }
+}
+
+impl<S: RawSender> From<&ChannelOutputState<S>> for SendError {
+    fn from(input: &ChannelOutputState<S>) -> Self {
+        PortState::from(input).into()
+    }
+}
+
+impl<S: RawSender> From<&ChannelOutputState<S>> for PortState {
+    fn from(input: &ChannelOutputState<S>) -> Self {
+        use ChannelOutputState::*;
+        match input {
+            Unconnected => PortState::Unconnected,
+            Connected(tx) => {
+                if tx.is_disconnected() {
+                    PortState::Disconnected
+                } else {
+                    PortState::Connected
+                }
+            },
+            Disconnected => PortState::Disconnected,
+            Closed => PortState::Closed,
+        }
+    }
+}
+
+/// An upstream connection to an input port, shared by an output port and
+/// its clones, which announces its disconnection exactly once.
+pub(crate) struct Upstream<S: RawSender> {
+    tx: S,
+    disconnected: AtomicBool,
+}
+
+impl<S: RawSender> Upstream<S> {
+    fn new(tx: &S) -> Arc<Self> {
+        Arc::new(Self {
+            tx: tx.clone(),
+            disconnected: AtomicBool::new(false),
+        })
+    }
+
+    /// Marks this connection as disconnected, returning whether it was
+    /// connected until now and thus the disconnection should be announced.
+    fn disconnect(&self) -> bool {
+        !self.disconnected.swap(true, Ordering::AcqRel)
+    }
+
+    /// Marks this connection as reconnected.
+    fn reconnect(&self) {
+        self.disconnected.store(false, Ordering::Release);
+    }
+}
+
+impl<S: RawSender> Drop for Upstream<S> {
+    fn drop(&mut self) {
+        // Announce the disconnection if there is room for it; otherwise,
+        // the input port accounts for it once the channel is torn down:
+        if self.disconnect() {
+            let _ = self.tx.try_send(PortEvent::Disconnect);
+        }
+    }
+}
+
+/// An output port sending at most `N` messages, unless unlimited, on the
+/// sending end `S` of a backend's channel.
+pub struct ChannelOutputs<S: RawSender, const N: isize> {
+    pub(crate) state: ChannelOutputState<S>,
+    /// The limit of `N` messages, unless unlimited, shared with all clones.
+    pub(crate) limit: MessageLimit,
+    pub(crate) upstream: Option<Arc<Upstream<S>>>,
+    /// The name of this port, if any.
+    pub(crate) name: Option<&'static str>,
+    /// The human-readable label of this port, if any.
+    pub(crate) label: Option<&'static str>,
+}
+
+impl<S: RawSender, const N: isize> Default for ChannelOutputs<S, N> {
+    fn default() -> Self {
+        Self {
+            state: ChannelOutputState::default(),
+            limit: MessageLimit::of::<N>(),
+            upstream: None,
+            name: None,
+            label: None,
+        }
+    }
+}
+
+impl<S: RawSender, const N: isize> Clone for ChannelOutputs<S, N> {
+    /// Clones this output port, sharing its upstream connection, which is
+    /// only disconnected once all clones have been closed or dropped.
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            limit: self.limit.clone(),
+            upstream: self.upstream.clone(),
+            name: self.name,
+            label: self.label,
+        }
+    }
+}
+
+impl<S: RawSender, const N: isize> ChannelOutputs<S, N>
+where
+    S::Message: 'static,
+{
+    pub fn type_id(&self) -> TypeId {
+        TypeId::of::<S::Message>()
+    }
+}
+
+impl<S: RawSender, const N: isize> core::fmt::Debug for ChannelOutputs<S, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut f = f.debug_tuple("Outputs");
+        if let Some(name) = self.name {
+            f.field(&name);
+        }
+        f.field(&self.state).finish()
+    }
+}
+
+impl<S: RawSender, const N: isize> ChannelOutputs<S, N> {
+    /// Sets the name of this port.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the human-readable label of this port.
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn close(&mut self) {
+        use ChannelOutputState::*;
+        match &self.state {
+            Closed => (), // idempotent
+            Connected(tx) => {
+                if self
+                    .upstream
+                    .as_ref()
+                    .is_some_and(|upstream| upstream.disconnect())
+                {
+                    let _ = tx.try_send(PortEvent::Disconnect);
+                }
+                self.upstream = None;
+                self.state = Closed;
+            },
+            Unconnected | Disconnected => {
+                self.state = Closed;
+            },
+        }
+    }
+
+    /// Checks whether this port's disconnection should be announced, which
+    /// is the case unless it was already announced.
+    fn should_announce_disconnect(&self) -> bool {
+        self.upstream
+            .as_ref()
+            .is_none_or(|upstream| upstream.disconnect())
+    }
+
+    /// Returns the sending end of this port's channel, after reserving one
+    /// of the remaining messages, along with whether it's the last one.
+    ///
+    /// Hands back the message if this port can't send it.
+    #[allow(clippy::type_complexity)]
+    fn reserve(
+        &self,
+        message: S::Message,
+    ) -> Result<(&S, bool, S::Message), (SendError, S::Message)> {
+        use ChannelOutputState::*;
+        let Connected(ref tx) = self.state else {
+            return Err(((&self.state).into(), message));
+        };
+        match self.limit.reserve() {
+            Ok(last) => Ok((tx, last, message)),
+            Err(error) => Err((error, message)),
+        }
+    }
+
+    pub fn direction(&self) -> PortDirection {
+        PortDirection::Output
+    }
+
+    pub fn state(&self) -> PortState {
+        match (&self.state).into() {
+            PortState::Connected if self.is_exhausted() => PortState::Disconnected,
+            state => state,
+        }
+    }
+
+    /// Checks whether this port has sent the maximum number of messages.
+    pub fn is_exhausted(&self) -> bool {
+        self.limit.is_exhausted()
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        use ChannelOutputState::*;
+        match self.state {
+            Connected(ref tx) => tx.capacity(),
+            _ => None,
+        }
+    }
+
+    pub fn max_capacity(&self) -> Option<usize> {
+        use ChannelOutputState::*;
+        match self.state {
+            Connected(ref tx) => tx.max_capacity(),
+            _ => None,
+        }
+    }
+
+    /// Sends a message, waiting until there is buffer capacity for it.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent.
+    pub async fn send(&self, message: S::Message) -> Result<(), SendMessageError<S::Message>>
+    where
+        S::Message: Send,
+    {
+        let (tx, last, message) = self
+            .reserve(message)
+            .map_err(|(error, message)| SendMessageError::new(error, message))?;
+        if let Err(event) = tx.send(PortEvent::Message(message)).await {
+            self.limit.release();
+            let message = event.into_message().unwrap();
+            return Err(SendMessageError::new(SendError::Disconnected, message));
+        }
+        if last && self.should_announce_disconnect() {
+            // The message was sent, so this can only fail if the input port
+            // is gone already:
+            let _ = tx.send(PortEvent::Disconnect).await;
+        }
+        Ok(())
+    }
+
+    pub async fn send_event(&self, event: PortEvent<S::Message>) -> Result<(), SendError>
+    where
+        S::Message: Send,
+    {
+        use ChannelOutputState::*;
+        let Connected(ref tx) = self.state else {
+            return Err((&self.state).into());
+        };
+        match event {
+            PortEvent::Connect => {
+                if let Some(upstream) = &self.upstream {
+                    upstream.reconnect();
+                }
+                tx.send(event).await.map_err(|_| SendError::Disconnected)
+            },
+            PortEvent::Disconnect => {
+                if self.should_announce_disconnect() {
+                    tx.send(event).await.map_err(|_| SendError::Disconnected)?;
+                }
+                Ok(())
+            },
+            PortEvent::Message(message) => Ok(self.send(message).await?),
+            PortEvent::Failure => tx.send(event).await.map_err(|_| SendError::Disconnected),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub async fn send_deadline(
+        &self,
+        message: S::Message,
+        deadline: std::time::Instant,
+    ) -> Result<(), TrySendError<S::Message>>
+    where
+        S::Message: Send,
+    {
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        self.send_timeout(message, timeout).await
+    }
+
+    /// Sends a message, waiting at most for the given duration.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent
+    /// before the timeout elapsed.
+    pub async fn send_timeout(
+        &self,
+        message: S::Message,
+        timeout: Duration,
+    ) -> Result<(), TrySendError<S::Message>>
+    where
+        S::Message: Send,
+    {
+        let (tx, last, message) = self
+            .reserve(message)
+            .map_err(|(error, message)| TrySendError::new(error, message))?;
+        let sent = tx.send_timeout(PortEvent::Message(message), timeout).await;
+        if let Err(error) = sent {
+            self.limit.release();
+            return Err(error.map(|event| event.into_message().unwrap()));
+        }
+        if last && self.should_announce_disconnect() {
+            // The message was sent, so this can only fail if the input port
+            // is gone already:
+            let _ = tx.send(PortEvent::Disconnect).await;
+        }
+        Ok(())
+    }
+
+    /// Sends a message, if there is buffer capacity for it.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent
+    /// immediately.
+    pub fn try_send(&self, message: S::Message) -> Result<(), TrySendError<S::Message>> {
+        let (tx, last, message) = self
+            .reserve(message)
+            .map_err(|(error, message)| TrySendError::new(error, message))?;
+        if let Err(error) = tx.try_send(PortEvent::Message(message)) {
+            self.limit.release();
+            return Err(error.map(|event| event.into_message().unwrap()));
+        }
+        if last && self.should_announce_disconnect() {
+            // The input port stops after `N` messages regardless, so the
+            // disconnect event is only sent if there is room for it:
+            let _ = tx.try_send(PortEvent::Disconnect);
+        }
+        Ok(())
+    }
+
+    /// Sends a message from synchronous code, blocking the current thread
+    /// until there is buffer capacity for it.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent, or
+    /// if called from within a system's task.
+    ///
+    /// # Panics
+    ///
+    /// With the Tokio backend, panics if called elsewhere on an async Tokio
+    /// worker thread, as Tokio's own blocking operations do.
+    pub fn blocking_send(&self, message: S::Message) -> Result<(), SendMessageError<S::Message>> {
+        if crate::io::is_async_context() {
+            return Err(SendMessageError::new(SendError::AsyncContext, message));
+        }
+        let (tx, last, message) = self
+            .reserve(message)
+            .map_err(|(error, message)| SendMessageError::new(error, message))?;
+        if let Err(event) = tx.blocking_send(PortEvent::Message(message)) {
+            self.limit.release();
+            let message = event.into_message().unwrap();
+            return Err(SendMessageError::new(SendError::Disconnected, message));
+        }
+        if last && self.should_announce_disconnect() {
+            // The message was sent, so this can only fail if the input port
+            // is gone already:
+            let _ = tx.blocking_send(PortEvent::Disconnect);
+        }
+        Ok(())
+    }
+}
+
+impl<S: RawSender, const N: isize> AsRef<S> for ChannelOutputs<S, N> {
+    fn as_ref(&self) -> &S {
+        use ChannelOutputState::*;
+        match self.state {
+            Connected(ref tx) => tx,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<S: RawSender, const N: isize> AsMut<S> for ChannelOutputs<S, N> {
+    fn as_mut(&mut self) -> &mut S {
+        use ChannelOutputState::*;
+        match self.state {
+            Connected(ref mut tx) => tx,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<S: RawSender, const N: isize> From<S> for ChannelOutputs<S, N> {
+    fn from(input: S) -> Self {
+        Self::from(&input)
+    }
+}
+
+impl<S: RawSender, const N: isize> From<&S> for ChannelOutputs<S, N> {
+    fn from(input: &S) -> Self {
+        use ChannelOutputState::*;
+        Self {
+            state: if input.is_disconnected() {
+                Disconnected
+            } else {
+                Connected(input.clone())
+            },
+            limit: MessageLimit::of::<N>(),
+            upstream: Some(Upstream::new(input)),
+            name: None,
+            label: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, const N: isize> crate::io::OutputPort<S::Message> for ChannelOutputs<S, N>
+where
+    S: RawSender + Send + Sync,
+    S::Message: Send + 'static,
+{
+    async fn send(&self, message: S::Message) -> Result<(), SendMessageError<S::Message>> {
+        self.send(message).await
+    }
+
+    async fn send_event(&self, event: PortEvent<S::Message>) -> Result<(), SendError> {
+        self.send_event(event).await
+    }
+
+    async fn send_timeout(
+        &self,
+        message: S::Message,
+        timeout: Duration,
+    ) -> Result<(), TrySendError<S::Message>> {
+        self.send_timeout(message, timeout).await
+    }
+
+    fn try_send(&self, message: S::Message) -> Result<(), TrySendError<S::Message>> {
+        self.try_send(message)
+    }
+}
+
+impl<S, const N: isize> crate::io::Port<S::Message> for ChannelOutputs<S, N>
+where
+    S: RawSender,
+    S::Message: Send,
+{
+    fn close(&mut self) {
+        self.close()
+    }
+
+    fn direction(&self) -> PortDirection {
+        self.direction()
+    }
+
+    fn state(&self) -> PortState {
+        self.state()
+    }
+}
+
+impl<S: RawSender, const N: isize> MaybeNamed for ChannelOutputs<S, N> {
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.name.map(Cow::Borrowed)
+    }
+}
+
+impl<S: RawSender, const N: isize> MaybeLabeled for ChannelOutputs<S, N> {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        self.label.map(Cow::Borrowed)
+    }
+}
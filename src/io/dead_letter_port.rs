@@ -0,0 +1,127 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::{SendError, SendMessageError, TrySendError},
+    io::{OutputPort, PortEvent, delegate_port},
+    model::{DeadLetter, OutputPortId},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::time::Duration;
+
+type Sink<T> = Arc<dyn OutputPort<DeadLetter<T>> + Send>;
+
+/// An output port that diverts the messages it can't deliver, because its
+/// peer is disconnected or closed, to a dead-letter port. So does
+/// `send_timeout` with the messages it can't deliver in time.
+///
+/// A diverted message counts as sent. Should the dead-letter port fail to
+/// take it as well, the original error is returned.
+pub struct DeadLetterPort<T: Send + 'static> {
+    inner: Box<dyn OutputPort<T> + Send>,
+    source: OutputPortId,
+    sink: Sink<T>,
+}
+
+impl<T: Send + 'static> DeadLetterPort<T> {
+    /// Wraps the given output port, identified as `source` in the dead
+    /// letters sent to the given dead-letter port.
+    pub fn new(
+        inner: Box<dyn OutputPort<T> + Send>,
+        source: OutputPortId,
+        sink: Box<dyn OutputPort<DeadLetter<T>> + Send>,
+    ) -> Self {
+        Self::with_sink(inner, source, Arc::from(sink))
+    }
+
+    /// Wraps the given output port, sharing the given dead-letter port with
+    /// other output ports.
+    pub(crate) fn with_sink(
+        inner: Box<dyn OutputPort<T> + Send>,
+        source: OutputPortId,
+        sink: Sink<T>,
+    ) -> Self {
+        Self {
+            inner,
+            source,
+            sink,
+        }
+    }
+}
+
+impl<T: Send + 'static> core::fmt::Debug for DeadLetterPort<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeadLetterPort")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> OutputPort<T> for DeadLetterPort<T> {
+    async fn send(&self, message: T) -> Result<(), SendMessageError<T>> {
+        let (error, message) = match self.inner.send(message).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error.into_parts(),
+        };
+        if !matches!(error, SendError::Disconnected | SendError::Closed) {
+            return Err(SendMessageError::new(error, message));
+        }
+        let letter = DeadLetter {
+            source: self.source,
+            error,
+            message,
+        };
+        self.sink
+            .send(letter)
+            .await
+            .map_err(|letter| SendMessageError::new(error, letter.into_inner().message))
+    }
+
+    async fn send_event(&self, event: PortEvent<T>) -> Result<(), SendError> {
+        match event {
+            PortEvent::Message(message) => Ok(self.send(message).await?),
+            _ => self.inner.send_event(event).await,
+        }
+    }
+
+    async fn send_timeout(&self, message: T, timeout: Duration) -> Result<(), TrySendError<T>> {
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        let (error, message) = match self.inner.send_timeout(message, timeout).await {
+            Err(TrySendError::Disconnected(message)) => (SendError::Disconnected, message),
+            Err(TrySendError::Closed(message)) => (SendError::Closed, message),
+            Err(TrySendError::Timeout(message)) => (SendError::Timeout, message),
+            result => return result,
+        };
+        let letter = DeadLetter {
+            source: self.source,
+            error,
+            message,
+        };
+        // The dead-letter port only gets what's left of the timeout:
+        let timeout = deadline.map_or(timeout, |deadline| {
+            deadline.saturating_duration_since(std::time::Instant::now())
+        });
+        self.sink
+            .send_timeout(letter, timeout)
+            .await
+            .map_err(|letter| TrySendError::new(error, letter.into_inner().message))
+    }
+
+    fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        let (error, message) = match self.inner.try_send(message) {
+            Err(TrySendError::Disconnected(message)) => (SendError::Disconnected, message),
+            Err(TrySendError::Closed(message)) => (SendError::Closed, message),
+            result => return result,
+        };
+        let letter = DeadLetter {
+            source: self.source,
+            error,
+            message,
+        };
+        self.sink
+            .try_send(letter)
+            .map_err(|letter| TrySendError::new(error, letter.into_inner().message))
+    }
+}
+
+delegate_port!(impl Port, MaybeNamed, MaybeLabeled for DeadLetterPort);
@@ -0,0 +1,120 @@
+// This is free and unencumbered software released into the public domain.
+
+/// Implements the given port traits for a port wrapper by delegating to the
+/// port it wraps, which is its `inner` field unless accessors are given:
+///
+/// ```ignore
+/// delegate_port!(impl Port, MaybeNamed, MaybeLabeled for LimitedInputPort);
+/// delegate_port!(impl OutputPort, Port for LeasedOutputPort via inner(), inner_mut());
+/// ```
+macro_rules! delegate_port {
+    (impl $($trait:ident),+ for $port:ident) => {
+        $crate::io::delegate_port!(@each [$($trait)+] $port [inner] [inner]);
+    };
+    (impl $($trait:ident),+ for $port:ident via $inner:ident(), $inner_mut:ident()) => {
+        $crate::io::delegate_port!(@each [$($trait)+] $port [$inner()] [$inner_mut()]);
+    };
+    (@each [$($trait:ident)+] $port:ident $inner:tt $inner_mut:tt) => {
+        $($crate::io::delegate_port!(@impl $trait $port $inner $inner_mut);)+
+    };
+    (@impl Port $port:ident [$($inner:tt)+] [$($inner_mut:tt)+]) => {
+        impl<T: Send + 'static> $crate::io::Port<T> for $port<T> {
+            fn close(&mut self) {
+                self.$($inner_mut)+.close()
+            }
+
+            fn direction(&self) -> $crate::io::PortDirection {
+                self.$($inner)+.direction()
+            }
+
+            fn state(&self) -> $crate::io::PortState {
+                self.$($inner)+.state()
+            }
+
+            fn capacity(&self) -> Option<usize> {
+                self.$($inner)+.capacity()
+            }
+
+            fn max_capacity(&self) -> Option<usize> {
+                self.$($inner)+.max_capacity()
+            }
+        }
+    };
+    (@impl InputPort $port:ident [$($inner:tt)+] [$($inner_mut:tt)+]) => {
+        #[::async_trait::async_trait]
+        impl<T: Send + 'static> $crate::io::InputPort<T> for $port<T> {
+            fn is_empty(&self) -> bool {
+                self.$($inner)+.is_empty()
+            }
+
+            async fn recv(&mut self) -> Result<Option<T>, $crate::error::RecvError> {
+                self.$($inner_mut)+.recv().await
+            }
+
+            async fn recv_event(
+                &mut self,
+            ) -> Result<Option<$crate::io::PortEvent<T>>, $crate::error::RecvError> {
+                self.$($inner_mut)+.recv_event().await
+            }
+
+            async fn recv_timeout(
+                &mut self,
+                timeout: ::core::time::Duration,
+            ) -> Result<Option<T>, $crate::error::TryRecvError> {
+                self.$($inner_mut)+.recv_timeout(timeout).await
+            }
+
+            fn try_recv(&mut self) -> Result<Option<T>, $crate::error::TryRecvError> {
+                self.$($inner_mut)+.try_recv()
+            }
+
+            #[cfg(feature = "stream")]
+            fn into_stream(self: ::alloc::boxed::Box<Self>) -> $crate::io::InputStream<T> {
+                self.$($inner)+.into_stream()
+            }
+        }
+    };
+    (@impl OutputPort $port:ident [$($inner:tt)+] [$($inner_mut:tt)+]) => {
+        #[::async_trait::async_trait]
+        impl<T: Send + 'static> $crate::io::OutputPort<T> for $port<T> {
+            async fn send(&self, message: T) -> Result<(), $crate::error::SendMessageError<T>> {
+                self.$($inner)+.send(message).await
+            }
+
+            async fn send_event(
+                &self,
+                event: $crate::io::PortEvent<T>,
+            ) -> Result<(), $crate::error::SendError> {
+                self.$($inner)+.send_event(event).await
+            }
+
+            async fn send_timeout(
+                &self,
+                message: T,
+                timeout: ::core::time::Duration,
+            ) -> Result<(), $crate::error::TrySendError<T>> {
+                self.$($inner)+.send_timeout(message, timeout).await
+            }
+
+            fn try_send(&self, message: T) -> Result<(), $crate::error::TrySendError<T>> {
+                self.$($inner)+.try_send(message)
+            }
+        }
+    };
+    (@impl MaybeNamed $port:ident [$($inner:tt)+] [$($inner_mut:tt)+]) => {
+        impl<T: Send + 'static> ::dogma::MaybeNamed for $port<T> {
+            fn name(&self) -> Option<::alloc::borrow::Cow<'_, str>> {
+                self.$($inner)+.name()
+            }
+        }
+    };
+    (@impl MaybeLabeled $port:ident [$($inner:tt)+] [$($inner_mut:tt)+]) => {
+        impl<T: Send + 'static> ::dogma::MaybeLabeled for $port<T> {
+            fn label(&self) -> Option<::alloc::borrow::Cow<'_, str>> {
+                self.$($inner)+.label()
+            }
+        }
+    };
+}
+
+pub(crate) use delegate_port;
@@ -0,0 +1,143 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::{SendError, SendMessageError, TrySendError},
+    io::{InputPort, OutputPort, Port, PortDirection, PortEvent, PortState, delegate_port},
+};
+use alloc::{
+    boxed::Box,
+    sync::{Arc, Weak},
+};
+use core::time::Duration;
+
+/// The sending end of an exported input port, whose messages are forwarded
+/// to the input port of a block by a task holding the given peer port.
+///
+/// This refuses any further messages once the block's input port is closed,
+/// such as when it received its maximum number of messages, rather than
+/// letting the forwarding task discard them.
+pub(crate) struct ExportedInputPort<T> {
+    inner: Box<dyn OutputPort<T> + Send>,
+    peer: Weak<dyn OutputPort<T> + Send>,
+}
+
+impl<T: Send + 'static> ExportedInputPort<T> {
+    pub(crate) fn new(
+        inner: Box<dyn OutputPort<T> + Send>,
+        peer: &Arc<dyn OutputPort<T> + Send>,
+    ) -> Self {
+        Self {
+            inner,
+            peer: Arc::downgrade(peer),
+        }
+    }
+
+    /// Checks that the block's input port still accepts messages.
+    fn check(&self) -> Result<(), SendError> {
+        match self.state() {
+            PortState::Connected => Ok(()),
+            state => Err(state.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> OutputPort<T> for ExportedInputPort<T> {
+    async fn send(&self, message: T) -> Result<(), SendMessageError<T>> {
+        if let Err(error) = self.check() {
+            return Err(SendMessageError::new(error, message));
+        }
+        self.inner.send(message).await
+    }
+
+    async fn send_event(&self, event: PortEvent<T>) -> Result<(), SendError> {
+        self.check()?;
+        self.inner.send_event(event).await
+    }
+
+    async fn send_timeout(&self, message: T, timeout: Duration) -> Result<(), TrySendError<T>> {
+        if let Err(error) = self.check() {
+            return Err(TrySendError::new(error, message));
+        }
+        self.inner.send_timeout(message, timeout).await
+    }
+
+    fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        if let Err(error) = self.check() {
+            return Err(TrySendError::new(error, message));
+        }
+        self.inner.try_send(message)
+    }
+}
+
+impl<T: Send + 'static> Port<T> for ExportedInputPort<T> {
+    fn close(&mut self) {
+        self.inner.close()
+    }
+
+    fn direction(&self) -> PortDirection {
+        self.inner.direction()
+    }
+
+    fn state(&self) -> PortState {
+        match (self.inner.state(), self.peer.upgrade()) {
+            (PortState::Connected, Some(peer)) => peer.state(),
+            (state, _) => state,
+        }
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+
+    fn max_capacity(&self) -> Option<usize> {
+        self.inner.max_capacity()
+    }
+}
+
+delegate_port!(impl MaybeNamed, MaybeLabeled for ExportedInputPort);
+
+/// The receiving end of an exported input port, which signals the task
+/// forwarding its messages to stop once closed or dropped.
+pub(crate) struct ForwardedInputPort<T> {
+    inner: Box<dyn InputPort<T> + Send>,
+    /// Dropped once this port is closed, which disconnects the signal.
+    closed: Option<Box<dyn OutputPort<()> + Send>>,
+}
+
+impl<T: Send + 'static> ForwardedInputPort<T> {
+    pub(crate) fn new(
+        inner: Box<dyn InputPort<T> + Send>,
+        closed: Box<dyn OutputPort<()> + Send>,
+    ) -> Self {
+        Self {
+            inner,
+            closed: Some(closed),
+        }
+    }
+}
+
+impl<T: Send + 'static> Port<T> for ForwardedInputPort<T> {
+    fn close(&mut self) {
+        self.inner.close();
+        self.closed = None;
+    }
+
+    fn direction(&self) -> PortDirection {
+        self.inner.direction()
+    }
+
+    fn state(&self) -> PortState {
+        self.inner.state()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+
+    fn max_capacity(&self) -> Option<usize> {
+        self.inner.max_capacity()
+    }
+}
+
+delegate_port!(impl InputPort, MaybeNamed, MaybeLabeled for ForwardedInputPort);
@@ -0,0 +1,74 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use crate::error::{Error, Result};
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use alloc::vec::Vec;
+
+/// How a running system reacts to the failure of one of its blocks.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum FailurePolicy {
+    /// Aborts the remaining blocks and returns the first error.
+    #[default]
+    FailFast,
+
+    /// Lets the remaining blocks run to completion and returns all errors.
+    CollectAll,
+}
+
+impl FailurePolicy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::FailFast => "fail-fast",
+            Self::CollectAll => "collect-all",
+        }
+    }
+}
+
+impl AsRef<str> for FailurePolicy {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// The errors of the failing blocks of a running system, collected
+/// according to its failure policy.
+#[derive(Debug)]
+pub(crate) struct Failures {
+    policy: FailurePolicy,
+    errors: Vec<Error>,
+}
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+impl Failures {
+    pub(crate) fn new(policy: FailurePolicy) -> Self {
+        Self {
+            policy,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Records the error of a failing block, returning it if the system
+    /// should fail right away.
+    pub(crate) fn record(&mut self, error: Error) -> Option<Error> {
+        match self.policy {
+            FailurePolicy::FailFast => Some(error),
+            FailurePolicy::CollectAll => {
+                self.errors.push(error);
+                None
+            },
+        }
+    }
+
+    /// Returns the collected errors, if any, as the system's result.
+    pub(crate) fn into_result(mut self) -> Result {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(Error::Aggregate(self.errors)),
+        }
+    }
+}
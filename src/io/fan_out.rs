@@ -0,0 +1,225 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::Result,
+    io::{InputPort, OutputPort},
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+/// A fan-out policy, determining how the messages sent on an output port
+/// are distributed over its multiple connections.
+pub enum FanOut<T> {
+    /// Sends a clone of each message to every connection.
+    Broadcast(fn(&T) -> T),
+
+    /// Sends each message to the next connection in turn.
+    RoundRobin,
+
+    /// Sends each message to the connection selected by hashing its key.
+    Partition(Arc<dyn Fn(&T) -> u64 + Send + Sync>),
+}
+
+impl<T: Clone> FanOut<T> {
+    /// Sends a clone of each message to every connection.
+    pub fn broadcast() -> Self {
+        Self::Broadcast(T::clone)
+    }
+}
+
+impl<T> FanOut<T> {
+    /// Sends each message to the next connection in turn.
+    pub fn round_robin() -> Self {
+        Self::RoundRobin
+    }
+
+    /// Sends each message to the connection selected by hashing the key
+    /// that the given function extracts from the message.
+    ///
+    /// Messages with equal keys are always sent to the same connection.
+    pub fn partition<K: Hash>(key: impl Fn(&T) -> K + Send + Sync + 'static) -> Self {
+        Self::Partition(Arc::new(move |message| {
+            let mut hasher = FnvHasher::default();
+            key(message).hash(&mut hasher);
+            hasher.finish()
+        }))
+    }
+
+    pub fn kind(&self) -> FanOutKind {
+        match self {
+            Self::Broadcast(_) => FanOutKind::Broadcast,
+            Self::RoundRobin => FanOutKind::RoundRobin,
+            Self::Partition(_) => FanOutKind::Partition,
+        }
+    }
+}
+
+impl<T> Clone for FanOut<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Broadcast(clone) => Self::Broadcast(*clone),
+            Self::RoundRobin => Self::RoundRobin,
+            Self::Partition(key) => Self::Partition(Arc::clone(key)),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for FanOut<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FanOut").field(&self.kind()).finish()
+    }
+}
+
+/// The kinds of fan-out policies (either broadcast, round-robin, or partition).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum FanOutKind {
+    Broadcast,
+    RoundRobin,
+    Partition,
+}
+
+impl FanOutKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Broadcast => "broadcast",
+            Self::RoundRobin => "round-robin",
+            Self::Partition => "partition",
+        }
+    }
+}
+
+impl AsRef<str> for FanOutKind {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::fmt::Display for FanOutKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A block that distributes its input messages over its output ports
+/// according to the given fan-out policy.
+///
+/// Output ports that fail to accept a message, for example because they
+/// have been disconnected, are skipped from then on. A round-robin or
+/// partitioned message is rerouted to the next remaining output port, and
+/// messages partitioned to a skipped output port keep going to the same
+/// remaining one. Distribution finishes once no output ports remain,
+/// without receiving any further messages.
+///
+/// A broadcast message meant for a skipped output port is dropped. To keep
+/// it, have the output port divert it to a dead-letter port, as a system
+/// does for a broadcast output port that has one.
+pub async fn fan_out<T: Send + 'static>(
+    mut inputs: Box<dyn InputPort<T> + Send>,
+    outputs: Vec<Box<dyn OutputPort<T> + Send>>,
+    policy: FanOut<T>,
+) -> Result {
+    // Skipped output ports are cleared rather than removed, so that the
+    // partition of each key doesn't change:
+    let mut outputs = outputs.into_iter().map(Some).collect::<Vec<_>>();
+    let mut next = 0;
+    while outputs.iter().any(Option::is_some) {
+        let Some(message) = inputs.recv().await? else {
+            break;
+        };
+        match policy {
+            FanOut::Broadcast(clone) => {
+                for output in &mut outputs {
+                    let sent = match output {
+                        Some(port) => port.send(clone(&message)).await.is_ok(),
+                        None => continue,
+                    };
+                    if !sent {
+                        *output = None;
+                    }
+                }
+            },
+            FanOut::RoundRobin => {
+                next = deliver(&mut outputs, next, message)
+                    .await
+                    .map_or(0, |index| index + 1);
+            },
+            FanOut::Partition(ref key) => {
+                let index = (key(&message) % outputs.len() as u64) as usize;
+                deliver(&mut outputs, index, message).await;
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Sends a message to the first remaining output port at or after the given
+/// index, wrapping around, and skipping output ports that fail to accept it.
+///
+/// Returns the index of the output port that accepted the message, or `None`
+/// if no output ports remain.
+async fn deliver<T: Send + 'static>(
+    outputs: &mut [Option<Box<dyn OutputPort<T> + Send>>],
+    start: usize,
+    mut message: T,
+) -> Option<usize> {
+    for index in (start..outputs.len()).chain(0..start) {
+        let Some(port) = &outputs[index] else {
+            continue;
+        };
+        match port.send(message).await {
+            Ok(()) => return Some(index),
+            Err(error) => message = error.into_inner(),
+        }
+        outputs[index] = None;
+    }
+    None
+}
+
+/// The 64-bit FNV-1a hash function, which is stable across runs and
+/// platforms, unlike the standard library's randomized default hasher.
+///
+/// Integers are hashed in little-endian byte order, and `usize` as `u64`,
+/// so that keys hash the same regardless of the platform's endianness and
+/// pointer width.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+}
@@ -1,8 +1,15 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::{error::RecvError, io::Port};
+use crate::{
+    error::{RecvError, TryRecvError},
+    io::{Port, PortEvent},
+};
 use alloc::{boxed::Box, vec::Vec};
-use core::any::TypeId;
+use core::{any::TypeId, time::Duration};
+
+/// A stream of messages received from an input port.
+#[cfg(feature = "stream")]
+pub type InputStream<T> = core::pin::Pin<Box<dyn tokio_stream::Stream<Item = T> + Send>>;
 
 #[async_trait::async_trait]
 pub trait InputPort<T: Send + 'static>: Port<T> {
@@ -13,8 +20,12 @@ pub trait InputPort<T: Send + 'static>: Port<T> {
     /// Checks if this port is empty.
     fn is_empty(&self) -> bool;
 
+    /// Receives the next message, waiting until one is available.
+    ///
+    /// Returns `Ok(None)` once the port has been disconnected and drained.
     async fn recv(&mut self) -> Result<Option<T>, RecvError>;
 
+    /// Receives all remaining messages until the port is disconnected.
     async fn recv_all(&mut self) -> Result<Vec<T>, RecvError> {
         let mut inputs = Vec::new();
         while let Some(input) = self.recv().await? {
@@ -23,9 +34,37 @@ pub trait InputPort<T: Send + 'static>: Port<T> {
         Ok(inputs)
     }
 
-    // TODO: recv_event
-    // TODO: recv_deadline
-    // TODO: recv_timeout
-    // TODO: try_recv
-    // TODO: into_stream
+    /// Receives the next port event, waiting until one is available.
+    ///
+    /// Besides messages, this delivers a connect event whenever an upstream
+    /// output port is (re)connected and a disconnect event whenever one is
+    /// disconnected, which [`recv`](Self::recv) skips over.
+    /// Returns `Ok(None)` once the port has been disconnected and drained.
+    async fn recv_event(&mut self) -> Result<Option<PortEvent<T>>, RecvError>;
+
+    /// Receives the next message, waiting until the given deadline.
+    ///
+    /// Returns an error if no message arrived before the deadline.
+    #[cfg(feature = "std")]
+    async fn recv_deadline(
+        &mut self,
+        deadline: std::time::Instant,
+    ) -> Result<Option<T>, TryRecvError> {
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        self.recv_timeout(timeout).await
+    }
+
+    /// Receives the next message, waiting at most for the given duration.
+    ///
+    /// Returns an error if no message arrived before the timeout elapsed.
+    async fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<T>, TryRecvError>;
+
+    /// Receives the next message, if one is immediately available.
+    ///
+    /// Returns an error if no message is currently available.
+    fn try_recv(&mut self) -> Result<Option<T>, TryRecvError>;
+
+    /// Converts this port into a stream of messages.
+    #[cfg(feature = "stream")]
+    fn into_stream(self: Box<Self>) -> InputStream<T>;
 }
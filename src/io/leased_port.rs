@@ -0,0 +1,111 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::{RecvError, TryRecvError},
+    io::{InputPort, OutputPort, PortEvent, delegate_port},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::{any::Any, time::Duration};
+use std::sync::Mutex;
+
+/// A slot holding a type-erased runtime port while it isn't leased out.
+pub(crate) type PortSlot = Arc<Mutex<Option<Box<dyn Any + Send>>>>;
+
+/// An input port on loan to a block, which returns to its slot once the
+/// block is done with it, so that a restarted block can take it again.
+pub(crate) struct LeasedInputPort<T: Send + 'static> {
+    inner: Option<Box<dyn InputPort<T> + Send>>,
+    slot: PortSlot,
+}
+
+impl<T: Send + 'static> LeasedInputPort<T> {
+    pub(crate) fn new(inner: Box<dyn InputPort<T> + Send>, slot: PortSlot) -> Self {
+        Self {
+            inner: Some(inner),
+            slot,
+        }
+    }
+
+    fn inner(&self) -> &(dyn InputPort<T> + Send) {
+        self.inner.as_deref().unwrap()
+    }
+
+    fn inner_mut(&mut self) -> &mut (dyn InputPort<T> + Send) {
+        self.inner.as_deref_mut().unwrap()
+    }
+}
+
+impl<T: Send + 'static> Drop for LeasedInputPort<T> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            *self.slot.lock().unwrap() = Some(Box::new(inner));
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> InputPort<T> for LeasedInputPort<T> {
+    fn is_empty(&self) -> bool {
+        self.inner().is_empty()
+    }
+
+    async fn recv(&mut self) -> Result<Option<T>, RecvError> {
+        self.inner_mut().recv().await
+    }
+
+    async fn recv_event(&mut self) -> Result<Option<PortEvent<T>>, RecvError> {
+        self.inner_mut().recv_event().await
+    }
+
+    async fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<T>, TryRecvError> {
+        self.inner_mut().recv_timeout(timeout).await
+    }
+
+    fn try_recv(&mut self) -> Result<Option<T>, TryRecvError> {
+        self.inner_mut().try_recv()
+    }
+
+    /// Converts this port into a stream, which ends the lease for good.
+    #[cfg(feature = "stream")]
+    fn into_stream(mut self: Box<Self>) -> crate::io::InputStream<T> {
+        self.inner.take().unwrap().into_stream()
+    }
+}
+
+delegate_port!(impl Port, MaybeNamed, MaybeLabeled for LeasedInputPort via inner(), inner_mut());
+
+/// An output port on loan to a block, which returns to its slot once the
+/// block is done with it, so that a restarted block can take it again.
+pub(crate) struct LeasedOutputPort<T: Send + 'static> {
+    inner: Option<Box<dyn OutputPort<T> + Send>>,
+    slot: PortSlot,
+}
+
+impl<T: Send + 'static> LeasedOutputPort<T> {
+    pub(crate) fn new(inner: Box<dyn OutputPort<T> + Send>, slot: PortSlot) -> Self {
+        Self {
+            inner: Some(inner),
+            slot,
+        }
+    }
+
+    fn inner(&self) -> &(dyn OutputPort<T> + Send) {
+        self.inner.as_deref().unwrap()
+    }
+
+    fn inner_mut(&mut self) -> &mut (dyn OutputPort<T> + Send) {
+        self.inner.as_deref_mut().unwrap()
+    }
+}
+
+impl<T: Send + 'static> Drop for LeasedOutputPort<T> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            *self.slot.lock().unwrap() = Some(Box::new(inner));
+        }
+    }
+}
+
+delegate_port!(
+    impl OutputPort, Port, MaybeNamed, MaybeLabeled for LeasedOutputPort via inner(), inner_mut()
+);
@@ -0,0 +1,178 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::{RecvError, SendError, SendMessageError, TryRecvError, TrySendError},
+    io::{
+        InputPort, MessageLimit, OutputPort, Port, PortDirection, PortEvent, PortState,
+        delegate_port,
+    },
+};
+use alloc::boxed::Box;
+use core::time::Duration;
+
+/// An input port that closes itself after receiving a maximum number of
+/// messages.
+pub(crate) struct LimitedInputPort<T> {
+    inner: Box<dyn InputPort<T> + Send>,
+    limit: MessageLimit,
+}
+
+impl<T: Send + 'static> LimitedInputPort<T> {
+    pub(crate) fn new(inner: Box<dyn InputPort<T> + Send>, limit: MessageLimit) -> Self {
+        Self { inner, limit }
+    }
+
+    /// Counts a received message, closing the port once the limit is
+    /// reached.
+    fn count(&mut self) {
+        if let Ok(true) = self.limit.reserve() {
+            self.inner.close();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> InputPort<T> for LimitedInputPort<T> {
+    fn is_empty(&self) -> bool {
+        self.limit.is_exhausted() || self.inner.is_empty()
+    }
+
+    async fn recv(&mut self) -> Result<Option<T>, RecvError> {
+        if self.limit.is_exhausted() {
+            return Ok(None);
+        }
+        let message = self.inner.recv().await?;
+        if message.is_some() {
+            self.count();
+        }
+        Ok(message)
+    }
+
+    async fn recv_event(&mut self) -> Result<Option<PortEvent<T>>, RecvError> {
+        if self.limit.is_exhausted() {
+            return Ok(None);
+        }
+        let event = self.inner.recv_event().await?;
+        if matches!(event, Some(PortEvent::Message(_))) {
+            self.count();
+        }
+        Ok(event)
+    }
+
+    async fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<T>, TryRecvError> {
+        if self.limit.is_exhausted() {
+            return Ok(None);
+        }
+        let message = self.inner.recv_timeout(timeout).await?;
+        if message.is_some() {
+            self.count();
+        }
+        Ok(message)
+    }
+
+    fn try_recv(&mut self) -> Result<Option<T>, TryRecvError> {
+        if self.limit.is_exhausted() {
+            return Ok(None);
+        }
+        let message = self.inner.try_recv()?;
+        if message.is_some() {
+            self.count();
+        }
+        Ok(message)
+    }
+
+    #[cfg(feature = "stream")]
+    fn into_stream(self: Box<Self>) -> crate::io::InputStream<T> {
+        use tokio_stream::StreamExt;
+        let remaining = self.limit.remaining().unwrap_or(usize::MAX);
+        Box::pin(self.inner.into_stream().take(remaining))
+    }
+}
+
+delegate_port!(impl Port, MaybeNamed, MaybeLabeled for LimitedInputPort);
+
+/// An output port that refuses any further messages after sending a maximum
+/// number of them.
+pub(crate) struct LimitedOutputPort<T> {
+    inner: Box<dyn OutputPort<T> + Send>,
+    limit: MessageLimit,
+}
+
+impl<T: Send + 'static> LimitedOutputPort<T> {
+    pub(crate) fn new(inner: Box<dyn OutputPort<T> + Send>, limit: MessageLimit) -> Self {
+        Self { inner, limit }
+    }
+
+    /// Reserves one of the remaining messages.
+    fn reserve(&self) -> Result<(), SendError> {
+        self.limit.reserve().map(|_| ())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> OutputPort<T> for LimitedOutputPort<T> {
+    async fn send(&self, message: T) -> Result<(), SendMessageError<T>> {
+        if let Err(error) = self.reserve() {
+            return Err(SendMessageError::new(error, message));
+        }
+        self.inner
+            .send(message)
+            .await
+            .inspect_err(|_| self.limit.release())
+    }
+
+    async fn send_event(&self, event: PortEvent<T>) -> Result<(), SendError> {
+        if !matches!(event, PortEvent::Message(_)) {
+            return self.inner.send_event(event).await;
+        }
+        self.reserve()?;
+        self.inner
+            .send_event(event)
+            .await
+            .inspect_err(|_| self.limit.release())
+    }
+
+    async fn send_timeout(&self, message: T, timeout: Duration) -> Result<(), TrySendError<T>> {
+        if let Err(error) = self.reserve() {
+            return Err(TrySendError::new(error, message));
+        }
+        let result = self.inner.send_timeout(message, timeout).await;
+        result.inspect_err(|_| self.limit.release())
+    }
+
+    fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        if let Err(error) = self.reserve() {
+            return Err(TrySendError::new(error, message));
+        }
+        self.inner
+            .try_send(message)
+            .inspect_err(|_| self.limit.release())
+    }
+}
+
+impl<T: Send + 'static> Port<T> for LimitedOutputPort<T> {
+    fn close(&mut self) {
+        self.inner.close()
+    }
+
+    fn direction(&self) -> PortDirection {
+        self.inner.direction()
+    }
+
+    fn state(&self) -> PortState {
+        match self.inner.state() {
+            PortState::Connected if self.limit.is_exhausted() => PortState::Disconnected,
+            state => state,
+        }
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+
+    fn max_capacity(&self) -> Option<usize> {
+        self.inner.max_capacity()
+    }
+}
+
+delegate_port!(impl MaybeNamed, MaybeLabeled for LimitedOutputPort);
@@ -0,0 +1,75 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::error::SendError;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A maximum number of messages to send or receive, counted across all
+/// clones of the port it belongs to.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MessageLimit {
+    max: Option<usize>,
+    sent: Arc<AtomicUsize>,
+}
+
+impl MessageLimit {
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    /// Creates a limit of the given maximum number of messages, if any.
+    pub(crate) fn new(max: Option<usize>) -> Self {
+        Self {
+            max,
+            sent: Arc::default(),
+        }
+    }
+
+    /// Creates a limit of the given maximum number of messages, counted
+    /// together with this one, so that a restarted block doesn't get a
+    /// fresh budget.
+    pub(crate) fn with_max(&self, max: usize) -> Self {
+        Self {
+            max: Some(max),
+            sent: Arc::clone(&self.sent),
+        }
+    }
+
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    /// Creates the limit of an output port with the cardinality `N`, which
+    /// is unlimited if negative.
+    pub(crate) fn of<const N: isize>() -> Self {
+        Self::new(usize::try_from(N).ok())
+    }
+
+    /// Checks whether the maximum number of messages has been sent.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.max
+            .is_some_and(|max| self.sent.load(Ordering::Acquire) >= max)
+    }
+
+    /// Returns how many more messages are allowed, if limited.
+    #[cfg(feature = "stream")]
+    pub(crate) fn remaining(&self) -> Option<usize> {
+        self.max
+            .map(|max| max.saturating_sub(self.sent.load(Ordering::Acquire)))
+    }
+
+    /// Reserves the sending of one more message, returning whether it is
+    /// the last one allowed.
+    pub(crate) fn reserve(&self) -> Result<bool, SendError> {
+        let Some(max) = self.max else {
+            return Ok(false);
+        };
+        self.sent
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .map(|n| n + 1 == max)
+            .map_err(|_| SendError::Exhausted)
+    }
+
+    /// Releases a reservation for a message that couldn't be sent.
+    pub(crate) fn release(&self) {
+        if self.max.is_some() {
+            self.sent.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
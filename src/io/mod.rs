@@ -2,15 +2,65 @@
 
 pub use crate::model::PortDirection;
 
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod blocking;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use blocking::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod channel_inputs;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub use channel_inputs::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod channel_outputs;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub use channel_outputs::*;
+
 mod connection;
 pub use connection::*;
 
+#[cfg(feature = "std")]
+mod dead_letter_port;
+#[cfg(feature = "std")]
+pub use dead_letter_port::*;
+
+mod delegate;
+pub(crate) use delegate::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod exported_port;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use exported_port::*;
+
+mod failure_policy;
+pub use failure_policy::*;
+
+mod fan_out;
+pub use fan_out::*;
+
 mod input_port;
 pub use input_port::*;
 
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+mod leased_port;
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+pub(crate) use leased_port::*;
+
+mod limited_port;
+pub(crate) use limited_port::*;
+
 mod message;
 pub use message::*;
 
+mod message_limit;
+pub(crate) use message_limit::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod named_port;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use named_port::*;
+
 mod output_port;
 pub use output_port::*;
 
@@ -23,5 +73,32 @@ pub use port_event::*;
 mod port_state;
 pub use port_state::*;
 
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod raw_channel;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub use raw_channel::*;
+
+mod restart_policy;
+pub use restart_policy::*;
+
 mod scheduler;
 pub use scheduler::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod select;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use select::*;
+
+mod spawner;
+pub use spawner::*;
+
+mod supervisor;
+pub use supervisor::*;
+
+mod timer;
+pub use timer::*;
+
+#[cfg(feature = "flume")]
+mod timeout;
+#[cfg(feature = "flume")]
+pub(crate) use timeout::*;
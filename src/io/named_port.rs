@@ -0,0 +1,69 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::io::{InputPort, OutputPort, delegate_port};
+use alloc::{borrow::Cow, boxed::Box};
+use dogma::{MaybeLabeled, MaybeNamed};
+
+/// An input port that carries the name and label of the port definition it
+/// was wired for.
+pub(crate) struct NamedInputPort<T> {
+    inner: Box<dyn InputPort<T> + Send>,
+    name: Option<&'static str>,
+    label: Option<&'static str>,
+}
+
+impl<T: Send + 'static> NamedInputPort<T> {
+    pub(crate) fn new(
+        inner: Box<dyn InputPort<T> + Send>,
+        name: Option<&'static str>,
+        label: Option<&'static str>,
+    ) -> Self {
+        Self { inner, name, label }
+    }
+}
+
+delegate_port!(impl InputPort, Port for NamedInputPort);
+
+impl<T> MaybeNamed for NamedInputPort<T> {
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.name.map(Cow::Borrowed)
+    }
+}
+
+impl<T> MaybeLabeled for NamedInputPort<T> {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        self.label.map(Cow::Borrowed)
+    }
+}
+
+/// An output port that carries the name and label of the port definition it
+/// was wired for.
+pub(crate) struct NamedOutputPort<T> {
+    inner: Box<dyn OutputPort<T> + Send>,
+    name: Option<&'static str>,
+    label: Option<&'static str>,
+}
+
+impl<T: Send + 'static> NamedOutputPort<T> {
+    pub(crate) fn new(
+        inner: Box<dyn OutputPort<T> + Send>,
+        name: Option<&'static str>,
+        label: Option<&'static str>,
+    ) -> Self {
+        Self { inner, name, label }
+    }
+}
+
+delegate_port!(impl OutputPort, Port for NamedOutputPort);
+
+impl<T> MaybeNamed for NamedOutputPort<T> {
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.name.map(Cow::Borrowed)
+    }
+}
+
+impl<T> MaybeLabeled for NamedOutputPort<T> {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        self.label.map(Cow::Borrowed)
+    }
+}
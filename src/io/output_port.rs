@@ -1,19 +1,53 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::error::SendError;
+use crate::{
+    error::{SendError, SendMessageError, TrySendError},
+    io::{Port, PortEvent},
+};
 use alloc::boxed::Box;
-use core::any::TypeId;
+use core::{any::TypeId, time::Duration};
 
+/// The sending end of a port.
+///
+/// Output ports are `Sync`, so that the futures sending on them can borrow
+/// them across await points.
 #[async_trait::async_trait]
-pub trait OutputPort<T: Send + 'static> {
+pub trait OutputPort<T: Send + 'static>: Port<T> + Sync {
     fn type_id(&self) -> TypeId {
         TypeId::of::<T>()
     }
 
-    async fn send(&self, message: T) -> Result<(), SendError>;
+    /// Sends a message, waiting until there is buffer capacity for it.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent.
+    async fn send(&self, message: T) -> Result<(), SendMessageError<T>>;
 
-    // TODO: send_event
-    // TODO: send_deadline
-    // TODO: send_timeout
-    // TODO: try_send
+    /// Sends a port event, waiting until there is buffer capacity for it.
+    async fn send_event(&self, event: PortEvent<T>) -> Result<(), SendError>;
+
+    /// Sends a message, waiting until the given deadline.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent
+    /// before the deadline.
+    #[cfg(feature = "std")]
+    async fn send_deadline(
+        &self,
+        message: T,
+        deadline: std::time::Instant,
+    ) -> Result<(), TrySendError<T>> {
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        self.send_timeout(message, timeout).await
+    }
+
+    /// Sends a message, waiting at most for the given duration.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent
+    /// before the timeout elapsed.
+    async fn send_timeout(&self, message: T, timeout: Duration) -> Result<(), TrySendError<T>>;
+
+    /// Sends a message, if there is buffer capacity for it.
+    ///
+    /// Returns an error handing back the message if it couldn't be sent
+    /// immediately.
+    fn try_send(&self, message: T) -> Result<(), TrySendError<T>>;
 }
@@ -1,12 +1,15 @@
 // This is free and unencumbered software released into the public domain.
 
-/// A port's state transition events (either connect, message, or disconnect).
+/// A port's state transition events (either connect, message, failure, or
+/// disconnect).
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum PortEvent<T> {
     Connect,
     Message(T),
+    /// The upstream block failed, and is restarted if it's supervised.
+    Failure,
     Disconnect,
 }
 
@@ -35,6 +38,11 @@ impl<T> PortEvent<T> {
         matches!(self, Self::Message(_))
     }
 
+    /// Checks whether the event is a failure event.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failure)
+    }
+
     /// Checks whether the event is a disconnect event.
     pub fn is_disconnect(&self) -> bool {
         matches!(self, Self::Disconnect)
@@ -44,6 +52,7 @@ impl<T> PortEvent<T> {
         match self {
             Self::Connect => "connect",
             Self::Message(_) => "message",
+            Self::Failure => "failure",
             Self::Disconnect => "disconnect",
         }
     }
@@ -0,0 +1,116 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    PortEvent,
+    error::{TryRecvError, TrySendError},
+};
+use core::time::Duration;
+
+/// The sending end of a backend's channel of port events.
+pub trait RawSender: Clone {
+    /// The type of the messages carried by the channel.
+    type Message;
+
+    /// Checks whether the receiving end is gone.
+    fn is_disconnected(&self) -> bool;
+
+    /// Returns the remaining buffer capacity, if known.
+    fn capacity(&self) -> Option<usize>;
+
+    /// Returns the maximum buffer capacity, if known.
+    fn max_capacity(&self) -> Option<usize>;
+
+    /// Sends an event, waiting until there is buffer capacity for it.
+    ///
+    /// Hands back the event if the receiving end is gone.
+    fn send(
+        &self,
+        event: PortEvent<Self::Message>,
+    ) -> impl Future<Output = Result<(), PortEvent<Self::Message>>> + Send
+    where
+        Self::Message: Send;
+
+    /// Sends an event, waiting at most for the given duration.
+    fn send_timeout(
+        &self,
+        event: PortEvent<Self::Message>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<(), TrySendError<PortEvent<Self::Message>>>> + Send
+    where
+        Self::Message: Send;
+
+    /// Sends an event, if there is buffer capacity for it.
+    fn try_send(
+        &self,
+        event: PortEvent<Self::Message>,
+    ) -> Result<(), TrySendError<PortEvent<Self::Message>>>;
+
+    /// Sends an event, blocking the current thread until there is buffer
+    /// capacity for it.
+    ///
+    /// Hands back the event if the receiving end is gone.
+    fn blocking_send(
+        &self,
+        event: PortEvent<Self::Message>,
+    ) -> Result<(), PortEvent<Self::Message>>;
+}
+
+/// The receiving end of a backend's channel of port events.
+///
+/// Once disconnected, a port only drains the events already buffered, which
+/// the `drain` argument of the receiving methods asks for.
+pub trait RawReceiver {
+    /// The type of the messages carried by the channel.
+    type Message;
+
+    /// Checks whether all sending ends are gone.
+    fn is_disconnected(&self) -> bool;
+
+    /// Refuses any further events, keeping those already buffered.
+    fn close(&mut self);
+
+    /// Checks whether no events are buffered.
+    fn is_empty(&self) -> bool;
+
+    /// Returns the remaining buffer capacity, if known.
+    fn capacity(&self) -> Option<usize>;
+
+    /// Returns the maximum buffer capacity, if known.
+    fn max_capacity(&self) -> Option<usize>;
+
+    /// Receives the next event, waiting until one is available.
+    ///
+    /// Returns `None` once all sending ends are gone and the buffer is
+    /// drained.
+    fn recv(
+        &mut self,
+        drain: bool,
+    ) -> impl Future<Output = Option<PortEvent<Self::Message>>> + Send
+    where
+        Self::Message: Send;
+
+    /// Receives the next event, if one is available.
+    ///
+    /// Returns `Ok(None)` once all sending ends are gone and the buffer is
+    /// drained.
+    fn try_recv(&mut self, drain: bool) -> Result<Option<PortEvent<Self::Message>>, TryRecvError>;
+
+    /// Receives the next event, blocking the current thread until one is
+    /// available.
+    fn blocking_recv(&mut self, drain: bool) -> Option<PortEvent<Self::Message>>;
+
+    /// Awaits the given future for at most the given duration, returning
+    /// `None` if the timeout elapsed first.
+    fn timeout<F: Future + Send>(
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Option<F::Output>> + Send
+    where
+        F::Output: Send;
+
+    /// Converts this receiving end into a stream of events.
+    #[cfg(feature = "stream")]
+    fn into_stream(self, drain: bool) -> super::InputStream<PortEvent<Self::Message>>
+    where
+        Self::Message: Send + 'static;
+}
@@ -0,0 +1,45 @@
+// This is free and unencumbered software released into the public domain.
+
+use core::time::Duration;
+
+/// How a supervised block is restarted once it completes.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum RestartPolicy {
+    /// Never restarts the block.
+    #[default]
+    Never,
+
+    /// Restarts the block whenever it fails, at most `max_retries` times,
+    /// waiting `backoff` before the first restart and twice as long before
+    /// each further one.
+    OnFailure {
+        max_retries: usize,
+        backoff: Duration,
+    },
+
+    /// Restarts the block whenever it fails, or completes while its inputs
+    /// may still receive messages, at most `max_restarts` times, waiting
+    /// `backoff` before each restart.
+    Always {
+        max_restarts: usize,
+        backoff: Duration,
+    },
+}
+
+impl RestartPolicy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Never => "never",
+            Self::OnFailure { .. } => "on-failure",
+            Self::Always { .. } => "always",
+        }
+    }
+}
+
+impl AsRef<str> for RestartPolicy {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
@@ -1,4 +1,17 @@
 // This is free and unencumbered software released into the public domain.
 
-#[async_trait::async_trait]
-pub trait Scheduler {}
+use super::Spawner;
+
+/// A scheduler, which spawns tasks through its [`Spawner`] implementation
+/// and runs them to completion.
+///
+/// This is object safe, so the scheduler to use can be chosen at runtime.
+pub trait Scheduler: Spawner {
+    /// Runs all spawned tasks to completion, blocking the current thread.
+    ///
+    /// This must not be called from within an async context.
+    fn run(&mut self);
+
+    /// Aborts all spawned tasks that haven't completed yet.
+    fn shutdown(&mut self);
+}
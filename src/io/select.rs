@@ -0,0 +1,25 @@
+// This is free and unencumbered software released into the public domain.
+
+use core::{pin::pin, task::Poll};
+
+/// The output of [`select`].
+pub(crate) enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// Waits for either of the given futures to complete, preferring the first.
+pub(crate) async fn select<L: Future, R: Future>(
+    left: L,
+    right: R,
+) -> Either<L::Output, R::Output> {
+    let mut left = pin!(left);
+    let mut right = pin!(right);
+    core::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = left.as_mut().poll(cx) {
+            return Poll::Ready(Either::Left(output));
+        }
+        right.as_mut().poll(cx).map(Either::Right)
+    })
+    .await
+}
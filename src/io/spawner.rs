@@ -0,0 +1,31 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Timer;
+use alloc::{boxed::Box, sync::Arc};
+use core::pin::Pin;
+
+/// A type-erased task to be spawned.
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Spawns tasks onto an executor.
+///
+/// This is what makes a system runnable on any executor: for example, a
+/// closure calling `smol::spawn(task).detach()` is a spawner.
+pub trait Spawner {
+    /// Spawns the given task, which is expected to run to completion in the
+    /// background.
+    fn spawn(&self, task: Task);
+
+    /// Returns the timer of the executor, if it has its own clock, such as
+    /// a virtual one, which then drives the timeouts and backoffs of the
+    /// system instead of real time.
+    fn timer(&self) -> Option<Arc<dyn Timer + Send + Sync>> {
+        None
+    }
+}
+
+impl<F: Fn(Task)> Spawner for F {
+    fn spawn(&self, task: Task) {
+        self(task)
+    }
+}
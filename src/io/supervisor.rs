@@ -0,0 +1,64 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use super::{RestartPolicy, SharedTimer};
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use crate::error::Result;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A handle for observing a supervised block.
+#[derive(Clone, Debug, Default)]
+pub struct SupervisorHandle(Arc<AtomicUsize>);
+
+impl SupervisorHandle {
+    /// Returns how many times the block has been restarted so far.
+    pub fn restarts(&self) -> usize {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// Runs the block created by the given factory, re-creating it whenever it
+/// completes and the given restart policy calls for it.
+///
+/// A block that completed successfully isn't restarted once `is_drained`
+/// reports that its inputs have nothing left to receive.
+///
+/// Returns the result of the block's last run.
+pub(crate) async fn supervise<F, B>(
+    policy: RestartPolicy,
+    supervisor: SupervisorHandle,
+    mut factory: F,
+    is_drained: impl Fn() -> bool,
+    timer: SharedTimer,
+) -> Result
+where
+    F: FnMut() -> B,
+    B: Future<Output = Result>,
+{
+    let mut backoff = match policy {
+        RestartPolicy::Never => core::time::Duration::ZERO,
+        RestartPolicy::OnFailure { backoff, .. } | RestartPolicy::Always { backoff, .. } => backoff,
+    };
+    loop {
+        let result = factory().await;
+        let restart = match policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure { max_retries, .. } => {
+                result.is_err() && supervisor.restarts() < max_retries
+            },
+            RestartPolicy::Always { max_restarts, .. } => {
+                (result.is_err() || !is_drained()) && supervisor.restarts() < max_restarts
+            },
+        };
+        if !restart {
+            return result;
+        }
+        timer.sleep(backoff).await;
+        if let RestartPolicy::OnFailure { .. } = policy {
+            backoff = backoff.saturating_mul(2);
+        }
+        supervisor.0.fetch_add(1, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,207 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Either, SharedTimer, select};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
+use core::{
+    cell::RefCell,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use std::{
+    sync::{Condvar, Mutex, OnceLock},
+    time::Instant,
+};
+
+std::thread_local! {
+    /// The timer of the task set whose task is being polled on this thread.
+    static TIMER: RefCell<Option<SharedTimer>> = const { RefCell::new(None) };
+}
+
+/// Awaits the given future for at most the given duration, returning `None`
+/// if the timeout elapsed first.
+///
+/// This doesn't depend on any particular async runtime: see [`sleep`].
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    match select(future, sleep(duration)).await {
+        Either::Left(output) => Some(output),
+        Either::Right(()) => None,
+    }
+}
+
+/// Waits until the given duration has elapsed.
+///
+/// Within a task of a system, this waits on the timer of the system's
+/// spawner, such as a virtual clock. Elsewhere, the timer is driven by a
+/// shared helper thread that wakes the waiting task once the duration has
+/// elapsed.
+pub(crate) fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    TIMER.with_borrow(|timer| match timer {
+        Some(timer) => timer.sleep(duration),
+        None => Box::pin(Delay::new(duration)),
+    })
+}
+
+/// A future making [`sleep`] wait on the given timer while it's polled.
+pub(crate) struct TimerContext<F> {
+    future: Pin<Box<F>>,
+    timer: SharedTimer,
+}
+
+impl<F> TimerContext<F> {
+    pub(crate) fn new(future: F, timer: SharedTimer) -> Self {
+        Self {
+            future: Box::pin(future),
+            timer,
+        }
+    }
+}
+
+impl<F: Future> Future for TimerContext<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        /// Restores the outer timer, even if the future panics.
+        struct Restore(Option<SharedTimer>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                TIMER.set(self.0.take());
+            }
+        }
+        let _restore = Restore(TIMER.replace(Some(Arc::clone(&self.timer))));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// A pending deadline, with a sequence number telling apart equal deadlines.
+type TimerKey = (Instant, u64);
+
+/// The pending deadlines of all delays, each with the waker to wake once it
+/// elapses, served by a single helper thread.
+#[derive(Default)]
+struct Timer {
+    state: Mutex<TimerState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct TimerState {
+    deadlines: BTreeMap<TimerKey, Waker>,
+    sequence: u64,
+}
+
+impl Timer {
+    /// Returns the shared timer, starting its thread on first use.
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<&'static Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            let timer: &'static Timer = Box::leak(Box::default());
+            std::thread::Builder::new()
+                .name("async-flow-timer".into())
+                .spawn(move || timer.run())
+                .expect("failed to spawn the timer thread");
+            timer
+        })
+    }
+
+    /// Registers or updates the waker for the given deadline.
+    fn register(&self, key: Option<TimerKey>, deadline: Instant, waker: &Waker) -> TimerKey {
+        let mut state = self.state.lock().unwrap();
+        let key = key.unwrap_or_else(|| {
+            state.sequence += 1;
+            (deadline, state.sequence)
+        });
+        let earliest = state
+            .deadlines
+            .keys()
+            .next()
+            .is_none_or(|first| key < *first);
+        match state.deadlines.get_mut(&key) {
+            Some(registered) => registered.clone_from(waker),
+            None => {
+                state.deadlines.insert(key, waker.clone());
+            },
+        }
+        if earliest {
+            self.changed.notify_one();
+        }
+        key
+    }
+
+    /// Forgets the given deadline, such as when its delay is dropped early.
+    fn cancel(&self, key: TimerKey) {
+        self.state.lock().unwrap().deadlines.remove(&key);
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            while let Some(entry) = state.deadlines.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                entry.remove().wake();
+            }
+            state = match state.deadlines.keys().next() {
+                Some(&(deadline, _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    self.changed.wait_timeout(state, timeout).unwrap().0
+                },
+                None => self.changed.wait(state).unwrap(),
+            };
+        }
+    }
+}
+
+struct Delay {
+    /// The deadline, unless too far in the future to represent.
+    deadline: Option<Instant>,
+    key: Option<TimerKey>,
+}
+
+impl Delay {
+    fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now().checked_add(duration),
+            key: None,
+        }
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(deadline) = self.deadline else {
+            return Poll::Pending; // never elapses
+        };
+        if Instant::now() >= deadline {
+            if let Some(key) = self.key.take() {
+                Timer::get().cancel(key);
+            }
+            return Poll::Ready(());
+        }
+        let key = Timer::get().register(self.key, deadline, cx.waker());
+        self.key = Some(key);
+        Poll::Pending
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            Timer::get().cancel(key);
+        }
+    }
+}
+
+/// The real-time timer driven by the shared helper thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ThreadTimer;
+
+impl super::Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(Delay::new(duration))
+    }
+}
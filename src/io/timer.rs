@@ -0,0 +1,14 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::boxed::Box;
+use core::{pin::Pin, time::Duration};
+
+/// Waits on a clock, such as an executor's own one.
+pub trait Timer {
+    /// Returns a future that completes once the given duration has elapsed.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A timer shared between the blocks of a system.
+pub(crate) type SharedTimer = alloc::sync::Arc<dyn Timer + Send + Sync>;
@@ -19,6 +19,9 @@ pub use io::*;
 
 pub mod model;
 
+#[cfg(feature = "executor")]
+pub mod executor;
+
 #[cfg(feature = "flume")]
 pub mod flume;
 #[cfg(all(feature = "flume", not(feature = "tokio")))]
@@ -26,8 +29,8 @@ pub use flume::*;
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
-#[cfg(all(feature = "tokio", not(feature = "flume")))]
-pub use tokio::*;
+#[cfg(feature = "tokio")]
+pub use tokio::*; // takes precedence over flume
 
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
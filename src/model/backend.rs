@@ -0,0 +1,351 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    BlockInputs, BlockOutputs, ErasedChannel, InputPortId, OutputPortId, PortType,
+    SystemDefinition, SystemExports,
+};
+use crate::{
+    error::Result,
+    io::{FailurePolicy, RawReceiver, RawSender, SharedTimer},
+};
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::{any::Any, pin::Pin};
+
+/// A type-erased task, such as a block's execution.
+pub(crate) type ErasedTask = Pin<Box<dyn Future<Output = Result> + Send>>;
+
+/// A runtime backend that a system definition can be prepared on.
+pub(crate) trait Backend {
+    /// Instantiates a channel for the given port type, merging the given
+    /// number of senders into one receiver.
+    fn channel(&self, port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel;
+
+    /// Instantiates a task forwarding messages from the given input port to
+    /// the given output port until the system is shut down, or until the
+    /// given `()` input port signals that the receiving end was closed.
+    fn forward(
+        &self,
+        port_type: PortType,
+        input: Box<dyn Any + Send>,
+        output: Box<dyn Any + Send>,
+        closed: Box<dyn Any + Send>,
+    ) -> ErasedTask;
+
+    /// Spawns the given task.
+    fn spawn(&mut self, task: ErasedTask);
+
+    /// Returns the registry of the system's exported ports and supervisors.
+    fn exports(&mut self) -> &mut SystemExports;
+
+    /// Returns the timer for waiting a given duration on this backend.
+    #[cfg(feature = "std")]
+    fn timer(&self) -> crate::io::SharedTimer;
+}
+
+/// The set of tasks that the blocks of a [`System`](super::System) run as
+/// on a runtime backend, along with the channels connecting them.
+pub trait Tasks: core::fmt::Debug {
+    /// The receiving end of the backend's channels.
+    type Receiver<T: Send + 'static>: RawReceiver<Message = T> + Send + 'static;
+
+    /// The sending end of the backend's channels.
+    type Sender<T: Send + 'static>: RawSender<Message = T> + Send + Sync + 'static;
+
+    /// A handle for aborting a spawned task.
+    type AbortHandle;
+
+    /// A handle for shutting down a running system.
+    type ShutdownHandle: Clone + Default + core::fmt::Debug + Send + Sync;
+
+    /// Spawns the given task.
+    fn spawn<F>(&mut self, task: F) -> Self::AbortHandle
+    where
+        F: Future<Output = Result> + Send + 'static;
+
+    /// Returns the timer for waiting a given duration on this backend.
+    fn timer(&self) -> SharedTimer;
+
+    /// Waits for all tasks to complete, collecting their errors according
+    /// to the given failure policy.
+    ///
+    /// Once shutdown is requested, the tasks are given its grace period to
+    /// complete, after which any remaining ones are aborted.
+    fn join_all(
+        &mut self,
+        policy: FailurePolicy,
+        shutdown: &Self::ShutdownHandle,
+    ) -> impl Future<Output = Result> + Send;
+
+    /// Instantiates a channel for the given port type, merging the given
+    /// number of senders into one receiver.
+    fn channel(port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel;
+
+    /// Instantiates a task forwarding messages from the given input port to
+    /// the given output port until the system is shut down, or until the
+    /// given `()` input port signals that the receiving end was closed.
+    fn forward(
+        port_type: PortType,
+        input: Box<dyn Any + Send>,
+        output: Box<dyn Any + Send>,
+        closed: Box<dyn Any + Send>,
+        shutdown: Self::ShutdownHandle,
+    ) -> ErasedTask;
+}
+
+impl SystemDefinition {
+    /// Connects the ports of all registered blocks and spawns them on the
+    /// given backend.
+    pub(crate) fn wire(&self, backend: &mut impl Backend) {
+        // Multiple connections to the same input port share one channel,
+        // so group the upstream output ports of each input port:
+        let mut upstreams = BTreeMap::<InputPortId, (PortType, Vec<OutputPortId>)>::new();
+        for ((output_id, input_id), port_type) in &self.connections {
+            upstreams
+                .entry(*input_id)
+                .or_insert_with(|| (*port_type, Vec::new()))
+                .1
+                .push(*output_id);
+        }
+        for (input_id, port_type) in self.inputs.iter() {
+            upstreams
+                .entry(*input_id)
+                .or_insert_with(|| (*port_type, Vec::new()));
+        }
+        // Dead-letter input ports are fed by one more sender per output port
+        // diverting its undeliverable messages to them:
+        let mut diverted = BTreeMap::<InputPortId, Vec<OutputPortId>>::new();
+        #[cfg(feature = "std")]
+        for (output_id, (input_id, port_type)) in &self.dead_letters {
+            upstreams
+                .entry(*input_id)
+                .or_insert_with(|| (*port_type, Vec::new()));
+            diverted.entry(*input_id).or_default().push(*output_id);
+        }
+        // Input ports with initial packets are fed by one more sender, via a
+        // task that sends the packets in order once the system starts:
+        let mut packets = BTreeMap::<InputPortId, Vec<_>>::new();
+        for (input_id, packet) in &self.initial_packets {
+            upstreams
+                .entry(*input_id)
+                .or_insert_with(|| (packet.port_type(), Vec::new()));
+            packets.entry(*input_id).or_default().push(packet.message());
+        }
+
+        // Runtime ports carry the names and labels of their definitions:
+        let names = self
+            .blocks
+            .iter()
+            .flat_map(|block| block.ports())
+            .filter(|port| port.name.is_some() || port.label.is_some())
+            .map(|port| (port.id, (port.name, port.label)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut inputs = BTreeMap::new();
+        let mut dead_letters = BTreeMap::new();
+        let mut downstreams = BTreeMap::<OutputPortId, (PortType, Vec<_>)>::new();
+        for (input_id, (port_type, output_ids)) in upstreams {
+            // An exported input port is fed by one more, external sender,
+            // via a forwarding task that stops once the system shuts down or
+            // the input port is closed, and which refuses messages as soon
+            // as the input port is closed:
+            let exported = self.inputs.contains(input_id);
+            let diverted = diverted.remove(&input_id).unwrap_or_default();
+            let packets = packets.remove(&input_id);
+            let senders = output_ids.len() + exported as usize + diverted.len();
+            let senders = senders + packets.is_some() as usize;
+            let (mut txs, mut rx) = backend.channel(port_type, 1, senders);
+            if exported {
+                let (mut external_txs, external_rx) = backend.channel(port_type, 1, 1);
+                let (external_tx, tx) =
+                    (port_type.export_input)(external_txs.pop().unwrap(), txs.pop().unwrap());
+                let (mut closed_txs, closed_rx) = backend.channel(PortType::of::<()>(), 1, 1);
+                rx = (port_type.forward_input)(rx, closed_txs.pop().unwrap());
+                backend.spawn(backend.forward(port_type, external_rx, tx, closed_rx));
+                backend.exports().export_input(input_id, external_tx);
+            }
+            if let Some(packets) = packets {
+                backend.spawn((port_type.send_all)(txs.pop().unwrap(), packets));
+            }
+            let diverted_txs = txs.split_off(output_ids.len());
+            dead_letters.extend(diverted.into_iter().zip(diverted_txs));
+            for (output_id, tx) in output_ids.into_iter().zip(txs) {
+                downstreams
+                    .entry(output_id)
+                    .or_insert_with(|| (port_type, Vec::new()))
+                    .1
+                    .push(tx);
+            }
+            let rx = match names.get(&input_id.into()) {
+                Some(&(name, label)) => (port_type.name_input)(rx, name, label),
+                None => rx,
+            };
+            inputs.insert(input_id, (port_type, rx));
+        }
+
+        for (output_id, port_type) in self.outputs.iter() {
+            // An exported output port feeds one more, external receiver,
+            // unless it's already connected and can't be fanned out, which
+            // `SystemBuilder` rejects but a hand-built definition may have:
+            let fanned_out = self.fan_outs.contains_key(output_id);
+            if downstreams.contains_key(output_id) && !fanned_out {
+                continue;
+            }
+            let (mut txs, rx) = backend.channel(*port_type, 1, 1);
+            downstreams
+                .entry(*output_id)
+                .or_insert_with(|| (*port_type, Vec::new()))
+                .1
+                .push(txs.pop().unwrap());
+            backend.exports().export_output(*output_id, rx);
+        }
+
+        let mut outputs = BTreeMap::new();
+        for (output_id, (port_type, mut txs)) in downstreams {
+            match self.fan_outs.get(&output_id) {
+                None => {
+                    outputs.insert(output_id, (port_type, txs.pop().unwrap()));
+                },
+                Some(policy) => {
+                    // A broadcast skips the connections that fail to accept a
+                    // message, so those messages are diverted from each one:
+                    #[cfg(feature = "std")]
+                    let txs = match (policy.kind(), dead_letters.remove(&output_id)) {
+                        (crate::io::FanOutKind::Broadcast, Some(sink)) => {
+                            (port_type.dead_letter)(txs, output_id, sink)
+                        },
+                        (_, Some(sink)) => {
+                            dead_letters.insert(output_id, sink);
+                            txs
+                        },
+                        (_, None) => txs,
+                    };
+                    let (mut tx, rx) = backend.channel(port_type, 1, 1);
+                    backend.spawn(policy.fan_out(rx, txs));
+                    outputs.insert(output_id, (port_type, tx.pop().unwrap()));
+                },
+            }
+        }
+
+        #[cfg(feature = "std")]
+        for (output_id, sink) in dead_letters {
+            if let Some((port_type, tx)) = outputs.remove(&output_id) {
+                let tx = (port_type.dead_letter)(alloc::vec![tx], output_id, sink).remove(0);
+                outputs.insert(output_id, (port_type, tx));
+            }
+        }
+
+        let mut outputs = outputs
+            .into_iter()
+            .map(|(output_id, (port_type, tx))| {
+                let tx = match names.get(&output_id.into()) {
+                    Some(&(name, label)) => (port_type.name_output)(tx, name, label),
+                    None => tx,
+                };
+                (output_id, (port_type, tx))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        for block in &self.blocks {
+            let block_inputs = block
+                .inputs()
+                .into_iter()
+                .filter_map(|id| inputs.remove(&id).map(|(typ, rx)| (id, typ, rx)))
+                .collect::<Vec<_>>();
+            let block_outputs = block
+                .outputs()
+                .into_iter()
+                .filter_map(|id| outputs.remove(&id).map(|(typ, tx)| (id, typ, tx)))
+                .collect::<Vec<_>>();
+
+            #[cfg(feature = "std")]
+            if block.restart_policy() != crate::io::RestartPolicy::Never {
+                spawn_supervised(backend, block, block_inputs, block_outputs);
+                continue;
+            }
+
+            let mut inputs = BlockInputs::new();
+            for (id, _, rx) in block_inputs {
+                inputs.insert(id, rx);
+            }
+            let mut outputs = BlockOutputs::new();
+            for (id, _, tx) in block_outputs {
+                outputs.insert(id, tx);
+            }
+            backend.spawn(Box::pin(block.execute(inputs, outputs)));
+        }
+    }
+}
+
+/// Spawns a supervised block, which borrows its runtime ports for each run
+/// so that they stay connected across restarts, along with the message
+/// counts of the ports that have a maximum cardinality.
+///
+/// Whenever a run fails, a failure event is sent downstream on each of the
+/// block's output ports.
+#[cfg(feature = "std")]
+fn spawn_supervised(
+    backend: &mut impl Backend,
+    block: &super::BlockHandle,
+    inputs: Vec<(InputPortId, PortType, Box<dyn Any + Send>)>,
+    outputs: Vec<(OutputPortId, PortType, Box<dyn Any + Send>)>,
+) {
+    use crate::io::{MessageLimit, PortSlot, SupervisorHandle};
+    use alloc::sync::Arc;
+    use std::sync::Mutex;
+
+    let slot = |port| -> PortSlot { Arc::new(Mutex::new(Some(port))) };
+    let inputs = inputs
+        .into_iter()
+        .map(|(id, typ, rx)| (id, typ, slot(rx), MessageLimit::default()))
+        .collect::<Vec<_>>();
+    let outputs = outputs
+        .into_iter()
+        .map(|(id, typ, tx)| (id, typ, slot(tx), MessageLimit::default()))
+        .collect::<Vec<_>>();
+
+    let supervisor = SupervisorHandle::default();
+    backend.exports().supervise(block, supervisor.clone());
+    let policy = block.restart_policy();
+    let timer = backend.timer();
+    let input_slots = inputs
+        .iter()
+        .map(|(_, typ, slot, _)| (*typ, Arc::clone(slot)))
+        .collect::<Vec<_>>();
+    let is_drained = move || {
+        !input_slots.is_empty() && input_slots.iter().all(|(typ, slot)| (typ.is_drained)(slot))
+    };
+    let block = block.clone();
+    let factory = move || {
+        let mut block_inputs = BlockInputs::new();
+        for (id, typ, slot, limit) in &inputs {
+            if let Some(rx) = slot.lock().unwrap().take() {
+                let rx = (typ.lease_input)(rx, Arc::clone(slot));
+                block_inputs.insert_limited(*id, rx, limit.clone());
+            }
+        }
+        let mut block_outputs = BlockOutputs::new();
+        for (id, typ, slot, limit) in &outputs {
+            if let Some(tx) = slot.lock().unwrap().take() {
+                let tx = (typ.lease_output)(tx, Arc::clone(slot));
+                block_outputs.insert_limited(*id, tx, limit.clone());
+            }
+        }
+        let run = block.execute(block_inputs, block_outputs);
+        let output_slots = outputs
+            .iter()
+            .map(|(_, typ, slot, _)| (*typ, Arc::clone(slot)))
+            .collect::<Vec<_>>();
+        async move {
+            let result = run.await;
+            if result.is_err() {
+                for (typ, slot) in output_slots {
+                    (typ.notify_failure)(slot).await;
+                }
+            }
+            result
+        }
+    };
+    backend.spawn(Box::pin(crate::io::supervise(
+        policy, supervisor, factory, is_drained, timer,
+    )));
+}
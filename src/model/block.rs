@@ -0,0 +1,61 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{BlockDefinition, BlockInputs, BlockOutputs};
+use crate::error::Result;
+use alloc::boxed::Box;
+
+/// An executable block.
+///
+/// # Examples
+///
+/// ```
+/// use async_flow::{
+///     Result,
+///     model::{Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs},
+///     model::{InputPortId, Inputs, OutputPortId, Outputs},
+/// };
+/// use std::borrow::Cow;
+///
+/// #[derive(Default)]
+/// struct Sqrt {
+///     input: Inputs<f64>,
+///     output: Outputs<f64>,
+/// }
+///
+/// impl BlockName for Sqrt {
+///     fn name(&self) -> Cow<'_, str> {
+///         "Sqrt".into()
+///     }
+/// }
+///
+/// impl BlockDefinition for Sqrt {
+///     fn inputs(&self) -> Vec<InputPortId> {
+///         vec![self.input.id()]
+///     }
+///
+///     fn outputs(&self) -> Vec<OutputPortId> {
+///         vec![self.output.id()]
+///     }
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl Block for Sqrt {
+///     async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+///         let (Some(mut input), Some(output)) =
+///             (inputs.take(&self.input), outputs.take(&self.output))
+///         else {
+///             return Ok(()); // unconnected
+///         };
+///         while let Some(x) = input.recv().await? {
+///             output.send(x.sqrt()).await?;
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait Block: BlockDefinition {
+    /// Executes this block until its work is done, receiving messages from
+    /// the given input ports and sending messages to the given output ports.
+    async fn execute(&self, inputs: BlockInputs, outputs: BlockOutputs) -> Result;
+}
@@ -1,17 +1,46 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::{InputPortId, OutputPortId};
+use super::{InputPortId, OutputPortId, PortDefinition, PortId};
 use alloc::vec::Vec;
 
 pub use dogma::Named as BlockName;
 
 /// A block definition.
-pub trait BlockDefinition: BlockName {
+pub trait BlockDefinition: BlockName + Send + Sync {
+    /// Returns the IDs of this block's input ports.
+    ///
+    /// Defaults to the input ports of [`ports`](Self::ports). Input ports
+    /// only listed here have no definition, so they're considered unbounded.
     fn inputs(&self) -> Vec<InputPortId> {
-        Vec::new()
+        self.ports()
+            .iter()
+            .filter_map(|port| match port.id {
+                PortId::Input(id) => Some(id),
+                PortId::Output(_) => None,
+            })
+            .collect()
     }
 
+    /// Returns the IDs of this block's output ports.
+    ///
+    /// Defaults to the output ports of [`ports`](Self::ports). Output ports
+    /// only listed here have no definition, so they're considered unbounded.
     fn outputs(&self) -> Vec<OutputPortId> {
+        self.ports()
+            .iter()
+            .filter_map(|port| match port.id {
+                PortId::Input(_) => None,
+                PortId::Output(id) => Some(id),
+            })
+            .collect()
+    }
+
+    /// Returns the definitions of this block's ports.
+    ///
+    /// Port definitions carry the message type and cardinality bounds of
+    /// each port, which are validated when building a system. Ports without
+    /// a definition are considered unbounded.
+    fn ports(&self) -> Vec<PortDefinition> {
         Vec::new()
     }
 }
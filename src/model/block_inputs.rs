@@ -0,0 +1,90 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{InputPortId, Inputs};
+use crate::io::{InputPort, LimitedInputPort, MessageLimit};
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::any::Any;
+
+/// The connected input ports handed to an executing block.
+#[derive(Default)]
+pub struct BlockInputs {
+    ports: BTreeMap<InputPortId, Box<dyn Any + Send>>,
+    /// The message counts that limited ports are charged against, if
+    /// carried over from an earlier run of the block.
+    limits: BTreeMap<InputPortId, MessageLimit>,
+}
+
+impl BlockInputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes ownership of the runtime port for the given input port.
+    ///
+    /// Returns `None` if the port isn't connected, has already been taken,
+    /// or was connected with a different message type.
+    ///
+    /// If the port declares a maximum cardinality, the returned port won't
+    /// receive more than that many messages.
+    pub fn take<T: Send + 'static, const MAX: isize, const MIN: isize>(
+        &mut self,
+        port: &Inputs<T, MAX, MIN>,
+    ) -> Option<Box<dyn InputPort<T> + Send>> {
+        let id = port.id();
+        let port = self.take_id(id)?;
+        let limit = self.limits.remove(&id).unwrap_or_default();
+        Some(match MAX {
+            ..0 => port,
+            max => Box::new(LimitedInputPort::new(port, limit.with_max(max as _))),
+        })
+    }
+
+    /// Takes ownership of the runtime port for the given input port ID.
+    pub fn take_id<T: Send + 'static>(
+        &mut self,
+        id: InputPortId,
+    ) -> Option<Box<dyn InputPort<T> + Send>> {
+        let port = self.ports.remove(&id)?;
+        port.downcast::<Box<dyn InputPort<T> + Send>>()
+            .map(|port| *port)
+            .ok()
+    }
+
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) fn insert(&mut self, id: InputPortId, port: Box<dyn Any + Send>) {
+        self.ports.insert(id, port);
+    }
+
+    /// Inserts a runtime port whose messages count against the given
+    /// limit, if the port declares a maximum cardinality.
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) fn insert_limited(
+        &mut self,
+        id: InputPortId,
+        port: Box<dyn Any + Send>,
+        limit: MessageLimit,
+    ) {
+        self.ports.insert(id, port);
+        self.limits.insert(id, limit);
+    }
+
+    pub fn contains(&self, id: InputPortId) -> bool {
+        self.ports.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ports.is_empty()
+    }
+}
+
+impl core::fmt::Debug for BlockInputs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.ports.keys().map(|id| id.0))
+            .finish()
+    }
+}
@@ -0,0 +1,148 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Block, BlockType};
+use alloc::{collections::BTreeMap, string::String, sync::Arc};
+
+/// A registry of block types, instantiating blocks by type name.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "std", feature = "tokio"))]
+/// # {
+/// use async_flow::model::{BlockRegistry, SystemBuilder};
+///
+/// let registry = BlockRegistry::with_builtins();
+/// let mut builder = SystemBuilder::new();
+/// let stdin = builder.instantiate(&registry, "stdin")?;
+/// let stdout = builder.instantiate(&registry, "stdout")?;
+/// builder.connect_by_id(stdin.outputs()[0], stdout.inputs()[0])?;
+/// let system = builder.build()?;
+/// # }
+/// # Ok::<(), async_flow::model::SystemBuildError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BlockRegistry {
+    types: BTreeMap<String, BlockType>,
+    #[cfg(feature = "serde")]
+    messages: BTreeMap<core::any::TypeId, MessageType>,
+}
+
+impl BlockRegistry {
+    /// Creates a new, empty block registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new block registry with the built-in `stdin`, `stdout`,
+    /// and `stderr` blocks, which read and write lines of text.
+    #[cfg(all(feature = "std", feature = "tokio"))]
+    pub fn with_builtins() -> Self {
+        use crate::tokio::{Stderr, Stdin, Stdout};
+        let mut registry = Self::new();
+        registry.register(Stdin::<String>::default);
+        registry.register(Stdout::<String>::default);
+        registry.register(Stderr::<String>::default);
+        #[cfg(feature = "serde")]
+        registry.register_message::<String>();
+        registry
+    }
+
+    /// Registers a block factory under the name of the blocks it
+    /// instantiates.
+    ///
+    /// Returns `false`, leaving the registry unchanged, if a block type of
+    /// the same name is already registered, such as the built-in `stdin`
+    /// for any `Stdin<T>`. Use [`BlockType::with_name`] to register it under
+    /// another name instead.
+    pub fn register<T: Block + 'static>(
+        &mut self,
+        factory: impl Fn() -> T + Send + Sync + 'static,
+    ) -> bool {
+        self.register_type(BlockType::new(factory))
+    }
+
+    /// Registers a block type.
+    ///
+    /// Returns `false`, leaving the registry unchanged, if a block type of
+    /// the same name is already registered.
+    pub fn register_type(&mut self, block_type: BlockType) -> bool {
+        use alloc::collections::btree_map::Entry;
+        match self.types.entry(block_type.name().into()) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(block_type);
+                true
+            },
+        }
+    }
+
+    /// Returns the block type of the given name, if registered.
+    pub fn get(&self, name: &str) -> Option<&BlockType> {
+        self.types.get(name)
+    }
+
+    /// Returns the registered block types, ordered by name.
+    pub fn types(&self) -> impl Iterator<Item = &BlockType> {
+        self.types.values()
+    }
+
+    /// Instantiates a new block of the given type name, if registered.
+    pub fn instantiate(&self, name: &str) -> Option<Arc<dyn Block>> {
+        self.get(name).map(BlockType::instantiate)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl BlockRegistry {
+    /// Registers a message type, so that graphs loaded with this registry
+    /// can send initial packets of type `T` and broadcast messages of
+    /// type `T`.
+    pub fn register_message<T>(&mut self)
+    where
+        T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let message = MessageType {
+            decode: |data| super::InitialPacket::from_json::<T>(data.clone()).ok(),
+            broadcast: || crate::io::FanOut::<T>::broadcast().into(),
+        };
+        self.messages.insert(core::any::TypeId::of::<T>(), message);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl super::GraphLoader for BlockRegistry {
+    fn block(&mut self, r#type: &str) -> Option<Arc<dyn Block>> {
+        self.instantiate(r#type)
+    }
+
+    fn packet(
+        &mut self,
+        port_type: super::PortType,
+        data: &serde_json::Value,
+    ) -> Option<super::InitialPacket> {
+        let message = self.messages.get(&port_type.id())?;
+        (message.decode)(data)
+    }
+
+    fn fan_out(
+        &mut self,
+        port_type: super::PortType,
+        kind: crate::io::FanOutKind,
+    ) -> Option<super::FanOutPolicy> {
+        use crate::io::FanOutKind::*;
+        match kind {
+            Broadcast => Some((self.messages.get(&port_type.id())?.broadcast)()),
+            RoundRobin => Some((port_type.round_robin)()),
+            Partition => None,
+        }
+    }
+}
+
+/// The type-erased operations on a registered message type.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug)]
+struct MessageType {
+    decode: fn(&serde_json::Value) -> Option<super::InitialPacket>,
+    broadcast: fn() -> super::FanOutPolicy,
+}
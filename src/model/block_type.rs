@@ -0,0 +1,85 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Block, PortSignature};
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+type BlockFactory = dyn Fn() -> Arc<dyn Block> + Send + Sync;
+
+/// A block type, instantiating blocks by way of a factory.
+#[derive(Clone)]
+pub struct BlockType {
+    name: String,
+    inputs: Vec<PortSignature>,
+    outputs: Vec<PortSignature>,
+    factory: Arc<BlockFactory>,
+}
+
+impl BlockType {
+    /// Creates a block type from a block factory.
+    ///
+    /// The factory is called once right away, to determine the name and
+    /// port signatures of the blocks it instantiates.
+    pub fn new<T: Block + 'static>(factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        let prototype = factory();
+        let ports = prototype.ports();
+        let signature = |id| {
+            let port = ports.iter().find(|port| port.id == id);
+            port.map_or(PortSignature::UNTYPED, PortSignature::from)
+        };
+        Self {
+            name: prototype.name().to_string(),
+            inputs: prototype
+                .inputs()
+                .into_iter()
+                .map(|id| signature(id.into()))
+                .collect(),
+            outputs: prototype
+                .outputs()
+                .into_iter()
+                .map(|id| signature(id.into()))
+                .collect(),
+            factory: Arc::new(move || Arc::new(factory())),
+        }
+    }
+
+    /// Sets the block type name, which defaults to the name of the blocks
+    /// it instantiates.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Returns the block type name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the signatures of the input ports, in order.
+    pub fn inputs(&self) -> &[PortSignature] {
+        &self.inputs
+    }
+
+    /// Returns the signatures of the output ports, in order.
+    pub fn outputs(&self) -> &[PortSignature] {
+        &self.outputs
+    }
+
+    /// Instantiates a new block of this type.
+    pub fn instantiate(&self) -> Arc<dyn Block> {
+        (self.factory)()
+    }
+}
+
+impl core::fmt::Debug for BlockType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BlockType")
+            .field("name", &self.name)
+            .field("inputs", &self.inputs)
+            .field("outputs", &self.outputs)
+            .finish_non_exhaustive()
+    }
+}
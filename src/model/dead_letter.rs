@@ -0,0 +1,13 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::OutputPortId;
+use crate::error::SendError;
+
+/// A message that couldn't be delivered, along with the output port it was
+/// sent on and the reason it couldn't be delivered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeadLetter<T> {
+    pub source: OutputPortId,
+    pub error: SendError,
+    pub message: T,
+}
@@ -0,0 +1,68 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::Result,
+    io::{FanOut, FanOutKind, InputPort, OutputPort},
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{any::Any, pin::Pin};
+
+type FanOutFn = dyn Fn(
+        Box<dyn Any + Send>,
+        Vec<Box<dyn Any + Send>>,
+    ) -> Pin<Box<dyn Future<Output = Result> + Send>>
+    + Send
+    + Sync;
+
+/// A type-erased fan-out policy for an output port.
+#[derive(Clone)]
+pub struct FanOutPolicy {
+    kind: FanOutKind,
+    #[cfg_attr(not(any(feature = "flume", feature = "tokio")), allow(dead_code))]
+    fan_out: Arc<FanOutFn>,
+}
+
+impl FanOutPolicy {
+    pub fn kind(&self) -> FanOutKind {
+        self.kind
+    }
+
+    #[cfg_attr(not(any(feature = "flume", feature = "tokio")), allow(dead_code))]
+    /// Returns a future distributing the messages received from the given
+    /// type-erased input port over the given type-erased output ports.
+    pub(crate) fn fan_out(
+        &self,
+        inputs: Box<dyn Any + Send>,
+        outputs: Vec<Box<dyn Any + Send>>,
+    ) -> Pin<Box<dyn Future<Output = Result> + Send>> {
+        (self.fan_out)(inputs, outputs)
+    }
+}
+
+impl<T: Send + 'static> From<FanOut<T>> for FanOutPolicy {
+    fn from(policy: FanOut<T>) -> Self {
+        Self {
+            kind: policy.kind(),
+            fan_out: Arc::new(move |inputs, outputs| {
+                let inputs = *inputs
+                    .downcast::<Box<dyn InputPort<T> + Send>>()
+                    .expect("fan-out input port type mismatch");
+                let outputs = outputs
+                    .into_iter()
+                    .map(|outputs| {
+                        *outputs
+                            .downcast::<Box<dyn OutputPort<T> + Send>>()
+                            .expect("fan-out output port type mismatch")
+                    })
+                    .collect();
+                Box::pin(crate::io::fan_out(inputs, outputs, policy.clone()))
+            }),
+        }
+    }
+}
+
+impl core::fmt::Debug for FanOutPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FanOutPolicy").field(&self.kind).finish()
+    }
+}
@@ -0,0 +1,595 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    GraphBlock, GraphBuilder, GraphConnection, GraphError, GraphLoader, GraphPacket, PortDirection,
+    SystemBuildError, SystemDefinition, SystemGraph,
+};
+use crate::io::RestartPolicy;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+use serde_json::Value;
+use thiserror::Error;
+
+/// A position in FBP source text, as a 1-based line and column.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl core::fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// An error in FBP source text, at the given position.
+#[derive(Debug, Error)]
+#[error("{position}: {kind}")]
+pub struct FbpError {
+    pub position: SourcePosition,
+    pub kind: FbpErrorKind,
+}
+
+#[derive(Debug, Error)]
+pub enum FbpErrorKind {
+    #[error("unexpected {found}, expected {expected}")]
+    Unexpected {
+        found: String,
+        expected: &'static str,
+    },
+
+    #[error("unterminated {0}")]
+    Unterminated(&'static str),
+
+    #[error("node without a component: {0}")]
+    UndeclaredNode(String),
+
+    #[error("node with conflicting components: {0}")]
+    ConflictingNode(String),
+
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+}
+
+impl FbpError {
+    fn new(position: SourcePosition, kind: impl Into<FbpErrorKind>) -> Self {
+        Self {
+            position,
+            kind: kind.into(),
+        }
+    }
+}
+
+impl From<SystemBuildError> for FbpErrorKind {
+    fn from(error: SystemBuildError) -> Self {
+        Self::Graph(error.into())
+    }
+}
+
+/// A graph parsed from the FBP DSL used by NoFlo and other FBP runtimes.
+///
+/// Each line, or comma-separated statement, declares nodes, connections,
+/// initial packets, or exported ports:
+///
+/// ```text
+/// # Comments run to the end of the line.
+/// INPORT=split.0:IN
+/// '42' -> 0 split(Split) 0 -> 0 sqrt(Sqrt) 0 -> 0 out(stdout)
+/// split 1 -> 0 log(stderr)
+/// ```
+///
+/// Nodes are declared as `name(Component)` on their first or any later
+/// occurrence, where the component is a block type name. Ports are
+/// referenced as in a [`SystemGraph`], by name or index, and connections are
+/// written `node OUT -> IN node`. Initial packets are parsed as JSON, or
+/// else taken as a string, so `'42'` sends a number but `'"42"'` and
+/// `'hello'` send strings. Exported ports are known outside the system by
+/// the names given after their colon, if any.
+#[derive(Clone, Debug, Default)]
+pub struct FbpGraph {
+    statements: Vec<(SourcePosition, Statement)>,
+}
+
+/// A declared node, with the name and component.
+type Node = (SourcePosition, (String, String));
+
+#[derive(Clone, Debug)]
+enum Statement {
+    Node(String, Option<String>),
+    Connection(String, String),
+    Packet(Value, String),
+    Export(PortDirection, String, Option<String>),
+}
+
+impl FbpGraph {
+    /// Parses FBP source text.
+    pub fn parse(source: &str) -> Result<Self, FbpError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            next: 0,
+            statements: Vec::new(),
+        };
+        parser.program()?;
+        Ok(Self {
+            statements: parser.statements,
+        })
+    }
+
+    /// Converts this graph into a [`SystemGraph`].
+    pub fn to_graph(&self) -> Result<SystemGraph, FbpError> {
+        let mut graph = SystemGraph::default();
+        for (_, (name, r#type)) in self.nodes()? {
+            let restart = RestartPolicy::Never;
+            graph.blocks.insert(name, GraphBlock { r#type, restart });
+        }
+        for (_, statement) in &self.statements {
+            match statement {
+                Statement::Node(..) => {},
+                Statement::Connection(from, to) => {
+                    let (from, to) = (from.clone(), to.clone());
+                    graph.connections.push(GraphConnection { from, to });
+                },
+                Statement::Packet(data, to) => {
+                    let (data, to) = (data.clone(), to.clone());
+                    graph.packets.push(GraphPacket { to, data });
+                },
+                Statement::Export(direction, port, name) => {
+                    let ports = match direction {
+                        PortDirection::Input => &mut graph.inputs,
+                        PortDirection::Output => &mut graph.outputs,
+                    };
+                    ports.push(port.clone());
+                    if let Some(name) = name {
+                        graph.export_names.insert(port.clone(), name.clone());
+                    }
+                },
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Builds a system definition from this graph, instantiating its blocks
+    /// with the given loader.
+    ///
+    /// Unlike going through [`to_graph`](Self::to_graph), this locates any
+    /// error in the source text.
+    pub fn build(&self, loader: &mut impl GraphLoader) -> Result<SystemDefinition, FbpError> {
+        let nodes = self.nodes()?;
+        let mut builder = GraphBuilder::new(loader);
+        for (position, (name, r#type)) in &nodes {
+            let block = GraphBlock {
+                r#type: r#type.clone(),
+                restart: RestartPolicy::Never,
+            };
+            builder
+                .block(name, &block)
+                .map_err(|error| FbpError::new(*position, error))?;
+        }
+        for (position, statement) in &self.statements {
+            let result = match statement {
+                Statement::Node(..) => Ok(()),
+                Statement::Connection(from, to) => builder.connect(from, to),
+                Statement::Packet(data, to) => builder.packet(to, data),
+                Statement::Export(PortDirection::Input, port, _) => builder.export_input(port),
+                Statement::Export(PortDirection::Output, port, _) => builder.export_output(port),
+            };
+            result.map_err(|error| FbpError::new(*position, error))?;
+        }
+
+        // Locate validation errors at the declaration of the port's node:
+        builder.build().map_err(|(error, owner)| {
+            let declaration = nodes
+                .iter()
+                .find(|(_, (name, _))| Some(name) == owner.as_ref());
+            let position = declaration.map(|(position, _)| *position);
+            FbpError::new(position.unwrap_or_default(), error)
+        })
+    }
+
+    /// Returns the declared nodes with their component, in declaration
+    /// order.
+    fn nodes(&self) -> Result<Vec<Node>, FbpError> {
+        let mut nodes = Vec::<(SourcePosition, (String, Option<String>))>::new();
+        for (position, statement) in &self.statements {
+            let Statement::Node(name, component) = statement else {
+                continue;
+            };
+            match nodes.iter_mut().find(|(_, (node, _))| node == name) {
+                None => nodes.push((*position, (name.clone(), component.clone()))),
+                Some((_, (_, declared))) => match (declared.as_ref(), component) {
+                    (_, None) => {},
+                    (None, Some(_)) => declared.clone_from(component),
+                    (Some(declared), Some(component)) if declared == component => {},
+                    (Some(_), Some(_)) => {
+                        let kind = FbpErrorKind::ConflictingNode(name.clone());
+                        return Err(FbpError::new(*position, kind));
+                    },
+                },
+            }
+        }
+        nodes
+            .into_iter()
+            .map(|(position, (name, component))| match component {
+                Some(component) => Ok((position, (name, component))),
+                None => Err(FbpError::new(position, FbpErrorKind::UndeclaredNode(name))),
+            })
+            .collect()
+    }
+}
+
+impl SystemGraph {
+    /// Parses a system graph from FBP source text.
+    ///
+    /// See [`FbpGraph`] for the syntax.
+    pub fn from_fbp(source: &str) -> Result<Self, FbpError> {
+        FbpGraph::parse(source)?.to_graph()
+    }
+
+    /// Formats this system graph as FBP source text.
+    ///
+    /// Returns an error if the graph has fan-outs, dead-letter connections,
+    /// restart policies, or names other than alphanumeric ones, which the
+    /// FBP DSL can't express.
+    pub fn to_fbp(&self) -> Result<String, GraphError> {
+        if !self.fan_outs.is_empty() {
+            return Err(GraphError::Unsupported("fan-outs"));
+        }
+        if !self.dead_letters.is_empty() {
+            return Err(GraphError::Unsupported("dead-letter connections"));
+        }
+        if self
+            .blocks
+            .values()
+            .any(|block| block.restart != RestartPolicy::Never)
+        {
+            return Err(GraphError::Unsupported("restart policies"));
+        }
+        // Names are printed as is, so they must read back as names:
+        let connections = self.connections.iter();
+        let ports = connections.flat_map(|GraphConnection { from, to }| [from, to]);
+        let ports = ports.chain(self.packets.iter().map(|packet| &packet.to));
+        let ports = ports.chain(&self.inputs).chain(&self.outputs);
+        let ports = ports.flat_map(|port| <[&str; 2]>::from(split(port)));
+        let names = self.blocks.keys().chain(self.export_names.values());
+        let mut names = names.map(String::as_str).chain(ports);
+        if !names.all(|name| !name.is_empty() && name.chars().all(is_name)) {
+            return Err(GraphError::Unsupported("non-alphanumeric names"));
+        }
+        if self.blocks.values().any(|block| {
+            let r#type = &block.r#type;
+            r#type.is_empty() || r#type.trim() != r#type || r#type.contains([')', ':', '\n'])
+        }) {
+            return Err(GraphError::Unsupported("unprintable component names"));
+        }
+
+        let mut output = String::new();
+        for (keyword, ports) in [("INPORT", &self.inputs), ("OUTPORT", &self.outputs)] {
+            for port in ports {
+                match self.export_names.get(port) {
+                    Some(name) => writeln!(output, "{keyword}={port}:{name}").unwrap(),
+                    None => writeln!(output, "{keyword}={port}").unwrap(),
+                }
+            }
+        }
+
+        // Declare each node on its first occurrence:
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|(name, block)| (name.as_str(), block));
+        let mut undeclared = blocks.collect::<BTreeMap<_, _>>();
+        let mut node = |name: &str| match undeclared.remove(name) {
+            Some(block) => format!("{name}({})", block.r#type),
+            None => name.to_string(),
+        };
+        for GraphPacket { to, data } in &self.packets {
+            let (to, port) = split(to);
+            writeln!(output, "{} -> {port} {}", packet(data), node(to)).unwrap();
+        }
+        for GraphConnection { from, to } in &self.connections {
+            let ((from, out), (to, r#in)) = (split(from), split(to));
+            let (from, to) = (node(from), node(to));
+            writeln!(output, "{from} {out} -> {in} {to}").unwrap();
+        }
+        for name in self.blocks.keys() {
+            let declaration = node(name);
+            if declaration != *name {
+                writeln!(output, "{declaration}").unwrap();
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Splits a `block.port` reference.
+fn split(port: &str) -> (&str, &str) {
+    port.rsplit_once('.').unwrap_or((port, ""))
+}
+
+/// Formats an initial packet, as a raw string unless it could be mistaken
+/// for JSON.
+fn packet(data: &Value) -> String {
+    let text = match data {
+        Value::String(text) if serde_json::from_str::<Value>(text).is_err() => text.clone(),
+        data => data.to_string(),
+    };
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Name(String),
+    Component(String),
+    Packet(String),
+    Arrow,
+    Dot,
+    Colon,
+    Equals,
+    Separator,
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Self::Name(name) => format!("`{name}`"),
+            Self::Component(component) => format!("`({component})`"),
+            Self::Packet(_) => "initial packet".into(),
+            Self::Arrow => "`->`".into(),
+            Self::Dot => "`.`".into(),
+            Self::Colon => "`:`".into(),
+            Self::Equals => "`=`".into(),
+            Self::Separator => "end of statement".into(),
+            Self::End => "end of input".into(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(SourcePosition, Token)>, FbpError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut position = SourcePosition { line: 1, column: 1 };
+    let advance = |position: &mut SourcePosition, c: char| {
+        if c == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    };
+    while let Some(&c) = chars.peek() {
+        let start = position;
+        chars.next();
+        advance(&mut position, c);
+        let token = match c {
+            '\n' | ',' => Token::Separator,
+            '#' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                continue;
+            },
+            c if c.is_whitespace() => continue,
+            '.' => Token::Dot,
+            ':' => Token::Colon,
+            '=' => Token::Equals,
+            '-' if chars.next_if_eq(&'>').is_some() => {
+                advance(&mut position, '>');
+                Token::Arrow
+            },
+            '(' => {
+                let mut component = String::new();
+                loop {
+                    let Some(c) = chars.next().filter(|&c| c != '\n') else {
+                        let kind = FbpErrorKind::Unterminated("component");
+                        return Err(FbpError::new(start, kind));
+                    };
+                    advance(&mut position, c);
+                    if c == ')' {
+                        break;
+                    }
+                    component.push(c);
+                }
+                // Ignore any component metadata:
+                let component = component.split(':').next().unwrap_or_default();
+                Token::Component(component.trim().into())
+            },
+            '\'' => {
+                let mut packet = String::new();
+                loop {
+                    let Some(c) = chars.next() else {
+                        let kind = FbpErrorKind::Unterminated("initial packet");
+                        return Err(FbpError::new(start, kind));
+                    };
+                    advance(&mut position, c);
+                    match c {
+                        '\'' => break,
+                        '\\' => {
+                            if let Some(c) = chars.next() {
+                                advance(&mut position, c);
+                                packet.push(c);
+                            }
+                        },
+                        c => packet.push(c),
+                    }
+                }
+                Token::Packet(packet)
+            },
+            c if is_name(c) => {
+                let mut name = String::from(c);
+                while let Some(c) = chars.next_if(|&c| is_name(c)) {
+                    advance(&mut position, c);
+                    name.push(c);
+                }
+                Token::Name(name)
+            },
+            c => {
+                let found = format!("`{c}`");
+                let kind = FbpErrorKind::Unexpected {
+                    found,
+                    expected: "a name, initial packet, or `->`",
+                };
+                return Err(FbpError::new(start, kind));
+            },
+        };
+        tokens.push((start, token));
+    }
+    tokens.push((position, Token::End));
+    Ok(tokens)
+}
+
+fn is_name(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+struct Parser<'a> {
+    tokens: &'a [(SourcePosition, Token)],
+    next: usize,
+    statements: Vec<(SourcePosition, Statement)>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &(SourcePosition, Token) {
+        &self.tokens[self.next.min(self.tokens.len() - 1)]
+    }
+
+    fn bump(&mut self) -> (SourcePosition, Token) {
+        let token = self.peek().clone();
+        self.next += 1;
+        token
+    }
+
+    fn unexpected(&self, expected: &'static str) -> FbpError {
+        let (position, token) = self.peek();
+        let found = token.describe();
+        FbpError::new(*position, FbpErrorKind::Unexpected { found, expected })
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<SourcePosition, FbpError> {
+        if self.peek().1 != token {
+            return Err(self.unexpected(expected));
+        }
+        Ok(self.bump().0)
+    }
+
+    fn name(&mut self, expected: &'static str) -> Result<(SourcePosition, String), FbpError> {
+        match self.peek() {
+            (position, Token::Name(name)) => {
+                let name = (*position, name.clone());
+                self.bump();
+                Ok(name)
+            },
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn program(&mut self) -> Result<(), FbpError> {
+        loop {
+            match &self.peek().1 {
+                Token::End => return Ok(()),
+                Token::Separator => {
+                    self.bump();
+                },
+                _ => {
+                    self.statement()?;
+                    if self.peek().1 != Token::End {
+                        self.expect(Token::Separator, "end of statement")?;
+                    }
+                },
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), FbpError> {
+        let export = match (&self.peek().1, &self.tokens.get(self.next + 1)) {
+            (Token::Name(keyword), Some((_, Token::Equals))) => match keyword.as_str() {
+                "INPORT" => Some(PortDirection::Input),
+                "OUTPORT" => Some(PortDirection::Output),
+                _ => None,
+            },
+            _ => None,
+        };
+        match export {
+            Some(direction) => self.export(direction),
+            None => self.chain(),
+        }
+    }
+
+    fn export(&mut self, direction: PortDirection) -> Result<(), FbpError> {
+        let (position, _) = self.bump();
+        self.bump();
+        let (_, node) = self.name("a node name")?;
+        self.expect(Token::Dot, "`.`")?;
+        let (_, port) = self.name("a port name")?;
+        let name = match self.peek().1 {
+            Token::Colon => {
+                self.bump();
+                Some(self.name("an exported port name")?.1)
+            },
+            _ => None,
+        };
+        let port = format!("{node}.{port}");
+        self.statements
+            .push((position, Statement::Export(direction, port, name)));
+        Ok(())
+    }
+
+    fn chain(&mut self) -> Result<(), FbpError> {
+        let mut upstream = match self.peek().clone() {
+            (_, Token::Packet(packet)) => {
+                self.bump();
+                let data = serde_json::from_str(&packet).unwrap_or(Value::String(packet));
+                Upstream::Packet(data)
+            },
+            _ => {
+                let node = self.node()?;
+                if !matches!(self.peek().1, Token::Name(_)) {
+                    return Ok(()); // a node declaration
+                }
+                let (_, port) = self.name("an output port name")?;
+                Upstream::Port(format!("{node}.{port}"))
+            },
+        };
+        loop {
+            let position = self.expect(Token::Arrow, "`->`")?;
+            let (_, port) = self.name("an input port name")?;
+            let node = self.node()?;
+            let input = format!("{node}.{port}");
+            let statement = match upstream {
+                Upstream::Packet(data) => Statement::Packet(data, input),
+                Upstream::Port(output) => Statement::Connection(output, input),
+            };
+            self.statements.push((position, statement));
+            if !matches!(self.peek().1, Token::Name(_)) {
+                return Ok(());
+            }
+            let (_, port) = self.name("an output port name")?;
+            upstream = Upstream::Port(format!("{node}.{port}"));
+        }
+    }
+
+    fn node(&mut self) -> Result<String, FbpError> {
+        let (position, name) = self.name("a node name")?;
+        let component = match self.peek() {
+            (_, Token::Component(component)) => {
+                let component = component.clone();
+                self.bump();
+                Some(component)
+            },
+            _ => None,
+        };
+        let statement = Statement::Node(name.clone(), component);
+        self.statements.push((position, statement));
+        Ok(name)
+    }
+}
+
+enum Upstream {
+    Packet(Value),
+    Port(String),
+}
@@ -0,0 +1,68 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::PortType;
+use alloc::{boxed::Box, sync::Arc};
+use core::any::Any;
+
+/// A message sent to an input port as soon as the system starts, also known
+/// as an initial information packet (IIP).
+#[derive(Clone)]
+pub struct InitialPacket {
+    port_type: PortType,
+    #[cfg_attr(not(any(feature = "flume", feature = "tokio")), allow(dead_code))]
+    message: Arc<dyn Fn() -> Box<dyn Any + Send> + Send + Sync>,
+    #[cfg(feature = "serde")]
+    json: Option<serde_json::Value>,
+}
+
+impl InitialPacket {
+    pub fn new<T: Clone + Send + Sync + 'static>(message: T) -> Self {
+        Self {
+            port_type: PortType::of::<T>(),
+            message: Arc::new(move || Box::new(message.clone())),
+            #[cfg(feature = "serde")]
+            json: None,
+        }
+    }
+
+    /// Decodes a message of type `T` from JSON.
+    ///
+    /// Unlike one instantiated with [`new`](Self::new), the resulting
+    /// packet can be saved as part of a [`SystemGraph`](super::SystemGraph).
+    #[cfg(feature = "serde")]
+    pub fn from_json<T>(json: serde_json::Value) -> Result<Self, serde_json::Error>
+    where
+        T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let message = serde_json::from_value::<T>(json.clone())?;
+        Ok(Self {
+            json: Some(json),
+            ..Self::new(message)
+        })
+    }
+
+    /// Returns the JSON encoding of the message, if known.
+    #[cfg(feature = "serde")]
+    pub fn json(&self) -> Option<&serde_json::Value> {
+        self.json.as_ref()
+    }
+
+    /// Returns the message type.
+    pub fn port_type(&self) -> PortType {
+        self.port_type
+    }
+
+    #[cfg_attr(not(any(feature = "flume", feature = "tokio")), allow(dead_code))]
+    /// Returns a new copy of the type-erased message, a `Box<T>`.
+    pub(crate) fn message(&self) -> Box<dyn Any + Send> {
+        (self.message)()
+    }
+}
+
+impl core::fmt::Debug for InitialPacket {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("InitialPacket")
+            .field(&self.port_type)
+            .finish()
+    }
+}
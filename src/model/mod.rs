@@ -1,14 +1,51 @@
 // This is free and unencumbered software released into the public domain.
 
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod backend;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use backend::*;
+
+mod block;
+pub use block::*;
+
 mod block_definition;
 pub use block_definition::*;
 
+mod block_inputs;
+pub use block_inputs::*;
+
+mod block_outputs;
+pub use block_outputs::*;
+
+mod block_registry;
+pub use block_registry::*;
+
+mod block_type;
+pub use block_type::*;
+
+mod dead_letter;
+pub use dead_letter::*;
+
+#[cfg(feature = "serde")]
+mod fbp;
+#[cfg(feature = "serde")]
+pub use fbp::*;
+
+mod fan_out_policy;
+pub use fan_out_policy::*;
+
+mod initial_packet;
+pub use initial_packet::*;
+
 mod inputs;
 pub use inputs::*;
 
 mod outputs;
 pub use outputs::*;
 
+mod port_definition;
+pub use port_definition::*;
+
 mod port_direction;
 pub use port_direction::*;
 
@@ -21,8 +58,31 @@ pub use port_id_map::*;
 mod port_id_set;
 pub use port_id_set::*;
 
+mod port_signature;
+pub use port_signature::*;
+
+mod port_type;
+pub use port_type::*;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod system;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub use system::*;
+
 mod system_definition;
 pub use system_definition::*;
 
 mod system_builder;
 pub use system_builder::*;
+
+mod system_diagram;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+mod system_exports;
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) use system_exports::*;
+
+#[cfg(feature = "serde")]
+mod system_graph;
+#[cfg(feature = "serde")]
+pub use system_graph::*;
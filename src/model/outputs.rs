@@ -1,6 +1,6 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::{OutputPortId, PortId};
+use super::{OutputPortId, PortId, PortType};
 use core::{
     any::{TypeId, type_name},
     marker::PhantomData,
@@ -17,7 +17,14 @@ pub type Output<T> = Outputs<T, 1, 0>;
 ///
 /// Note that `Outputs` doesn't implement `Copy`, whereas `Inputs` does.
 #[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Outputs<T, const MAX: isize = -1, const MIN: isize = 0>(OutputPortId, PhantomData<T>);
+pub struct Outputs<T, const MAX: isize = -1, const MIN: isize = 0> {
+    id: OutputPortId,
+    name: Option<&'static str>,
+    label: Option<&'static str>,
+    /// Ports don't hold messages, so they're `Send` and `Sync` whether or
+    /// not `T` is.
+    r#type: PhantomData<fn() -> T>,
+}
 
 impl<T: 'static, const MAX: isize, const MIN: isize> Outputs<T, MAX, MIN> {
     pub fn type_id(&self) -> TypeId {
@@ -25,25 +32,66 @@ impl<T: 'static, const MAX: isize, const MIN: isize> Outputs<T, MAX, MIN> {
     }
 }
 
+impl<T: Send + 'static, const MAX: isize, const MIN: isize> Outputs<T, MAX, MIN> {
+    pub fn port_type(&self) -> PortType {
+        PortType::of::<T>()
+    }
+}
+
 impl<T, const MAX: isize, const MIN: isize> Default for Outputs<T, MAX, MIN> {
     fn default() -> Self {
         static COUNTER: AtomicIsize = AtomicIsize::new(1);
         let id = COUNTER.fetch_add(1, Ordering::AcqRel);
-        Self(OutputPortId(id), PhantomData)
+        Self {
+            id: OutputPortId(id),
+            name: None,
+            label: None,
+            r#type: PhantomData,
+        }
     }
 }
 
 impl<T, const MAX: isize, const MIN: isize> core::fmt::Debug for Outputs<T, MAX, MIN> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_tuple(&alloc::format!("Outputs<{}>", type_name::<T>()))
-            .field(&self.0)
-            .finish()
+        let mut f = f.debug_tuple(&alloc::format!("Outputs<{}>", type_name::<T>()));
+        f.field(&self.id);
+        if let Some(name) = self.name {
+            f.field(&name);
+        }
+        f.finish()
     }
 }
 
 impl<T, const MAX: isize, const MIN: isize> Outputs<T, MAX, MIN> {
+    /// Creates a new port with the given name.
+    pub fn named(name: &'static str) -> Self {
+        Self::default().with_name(name)
+    }
+
+    /// Sets the name of this port, which identifies it within its block.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the human-readable label of this port.
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
     pub fn id(&self) -> OutputPortId {
-        self.0
+        self.id
+    }
+
+    /// Returns the name of this port, if any.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Returns the human-readable label of this port, if any.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
     }
 
     /// Returns the cardinality of this connection.
@@ -58,30 +106,30 @@ impl<T, const MAX: isize, const MIN: isize> Outputs<T, MAX, MIN> {
     }
 }
 
-impl<T, const MAX: isize, const MIN: isize> Into<OutputPortId> for &Outputs<T, MAX, MIN> {
-    fn into(self) -> OutputPortId {
-        self.0
+impl<T, const MAX: isize, const MIN: isize> From<&Outputs<T, MAX, MIN>> for OutputPortId {
+    fn from(input: &Outputs<T, MAX, MIN>) -> Self {
+        input.id
     }
 }
 
-impl<T: 'static, const MAX: isize, const MIN: isize> Into<(OutputPortId, TypeId)>
-    for &Outputs<T, MAX, MIN>
+impl<T: Send + 'static, const MAX: isize, const MIN: isize> From<&Outputs<T, MAX, MIN>>
+    for (OutputPortId, PortType)
 {
-    fn into(self) -> (OutputPortId, TypeId) {
-        (self.0, self.type_id())
+    fn from(input: &Outputs<T, MAX, MIN>) -> Self {
+        (input.id, input.port_type())
     }
 }
 
-impl<T, const MAX: isize, const MIN: isize> Into<PortId> for &Outputs<T, MAX, MIN> {
-    fn into(self) -> PortId {
-        self.0.into()
+impl<T, const MAX: isize, const MIN: isize> From<&Outputs<T, MAX, MIN>> for PortId {
+    fn from(input: &Outputs<T, MAX, MIN>) -> Self {
+        input.id.into()
     }
 }
 
-impl<T: 'static, const MAX: isize, const MIN: isize> Into<(PortId, TypeId)>
-    for &Outputs<T, MAX, MIN>
+impl<T: Send + 'static, const MAX: isize, const MIN: isize> From<&Outputs<T, MAX, MIN>>
+    for (PortId, PortType)
 {
-    fn into(self) -> (PortId, TypeId) {
-        (self.0.into(), self.type_id())
+    fn from(input: &Outputs<T, MAX, MIN>) -> Self {
+        (input.id.into(), input.port_type())
     }
 }
@@ -0,0 +1,90 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Inputs, Outputs, PortDirection, PortId, PortType};
+use core::ops::Bound;
+
+/// A port definition, describing an input or output port of a block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PortDefinition {
+    /// The port's identifier.
+    pub id: PortId,
+
+    /// The port's name, if any, which identifies it within its block.
+    pub name: Option<&'static str>,
+
+    /// The port's human-readable label, if any.
+    pub label: Option<&'static str>,
+
+    /// The port's message type.
+    pub r#type: PortType,
+
+    /// The minimum number of connections that the port requires.
+    pub min: usize,
+
+    /// The maximum number of connections, as well as messages, that the
+    /// port allows, if bounded.
+    pub max: Option<usize>,
+}
+
+impl PortDefinition {
+    /// The dataflow direction of this port.
+    pub fn direction(&self) -> PortDirection {
+        match self.id {
+            PortId::Input(_) => PortDirection::Input,
+            PortId::Output(_) => PortDirection::Output,
+        }
+    }
+
+    /// Checks whether the given number of connections is within this port's
+    /// cardinality bounds.
+    pub fn allows(&self, connections: usize) -> bool {
+        connections >= self.min && self.max.is_none_or(|max| connections <= max)
+    }
+}
+
+fn bounds((min, max): (Bound<usize>, Bound<usize>)) -> (usize, Option<usize>) {
+    use Bound::*;
+    let min = match min {
+        Included(min) => min,
+        Excluded(min) => min + 1,
+        Unbounded => 0,
+    };
+    let max = match max {
+        Included(max) => Some(max),
+        Excluded(max) => Some(max.saturating_sub(1)),
+        Unbounded => None,
+    };
+    (min, max)
+}
+
+impl<T: Send + 'static, const MAX: isize, const MIN: isize> From<&Inputs<T, MAX, MIN>>
+    for PortDefinition
+{
+    fn from(input: &Inputs<T, MAX, MIN>) -> Self {
+        let (min, max) = bounds(Inputs::<T, MAX, MIN>::cardinality());
+        Self {
+            id: input.id().into(),
+            name: input.name(),
+            label: input.label(),
+            r#type: input.port_type(),
+            min,
+            max,
+        }
+    }
+}
+
+impl<T: Send + 'static, const MAX: isize, const MIN: isize> From<&Outputs<T, MAX, MIN>>
+    for PortDefinition
+{
+    fn from(output: &Outputs<T, MAX, MIN>) -> Self {
+        let (min, max) = bounds(Outputs::<T, MAX, MIN>::cardinality());
+        Self {
+            id: output.id().into(),
+            name: output.name(),
+            label: output.label(),
+            r#type: output.port_type(),
+            min,
+            max,
+        }
+    }
+}
@@ -52,9 +52,7 @@ impl<K: Into<isize> + Ord, V: Debug> PortIdMap<K, V> {
 
 impl<K: Into<isize> + Copy + Ord, V: Debug> PortIdMap<K, V> {
     pub fn range(&self) -> Option<RangeInclusive<isize>> {
-        let Some(&min) = self.first() else {
-            return None;
-        };
+        let &min = self.first()?;
         let Some(&max) = self.last() else {
             unreachable!()
         };
@@ -57,9 +57,7 @@ impl<K: Into<isize> + Ord> PortIdSet<K> {
 
 impl<K: Into<isize> + Copy + Ord> PortIdSet<K> {
     pub fn range(&self) -> Option<RangeInclusive<K>> {
-        let Some(&min) = self.first() else {
-            return None;
-        };
+        let &min = self.first()?;
         let Some(&max) = self.last() else {
             unreachable!()
         };
@@ -70,13 +68,7 @@ impl<K: Into<isize> + Copy + Ord> PortIdSet<K> {
 impl<K: Into<isize> + Copy> Debug for PortIdSet<K> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_list()
-            .entries(
-                &self
-                    .0
-                    .iter()
-                    .map(|id| Into::<isize>::into(*id))
-                    .collect::<Vec<_>>(),
-            )
+            .entries(self.0.iter().map(|id| Into::<isize>::into(*id)))
             .finish()
     }
 }
@@ -0,0 +1,46 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{PortDefinition, PortType};
+
+/// The signature of a port of a block type, which unlike a
+/// [`PortDefinition`] isn't tied to a block instance.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PortSignature {
+    /// The port's name, if any.
+    pub name: Option<&'static str>,
+
+    /// The port's human-readable label, if any.
+    pub label: Option<&'static str>,
+
+    /// The port's message type, if defined.
+    pub r#type: Option<PortType>,
+
+    /// The minimum number of connections that the port requires.
+    pub min: usize,
+
+    /// The maximum number of connections that the port allows, if bounded.
+    pub max: Option<usize>,
+}
+
+impl PortSignature {
+    /// The signature of a port without a [`PortDefinition`].
+    pub const UNTYPED: Self = Self {
+        name: None,
+        label: None,
+        r#type: None,
+        min: 0,
+        max: None,
+    };
+}
+
+impl From<&PortDefinition> for PortSignature {
+    fn from(port: &PortDefinition) -> Self {
+        Self {
+            name: port.name,
+            label: port.label,
+            r#type: Some(port.r#type),
+            min: port.min,
+            max: port.max,
+        }
+    }
+}
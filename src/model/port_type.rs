@@ -0,0 +1,314 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::any::{TypeId, type_name};
+#[cfg(any(feature = "flume", feature = "tokio"))]
+use core::{any::Any, pin::Pin};
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A type-erased set of runtime port endpoints, wrapping one or more
+/// `Box<dyn OutputPort<T> + Send>` and a `Box<dyn InputPort<T> + Send>`.
+pub(crate) type ErasedChannel = (Vec<Box<dyn Any + Send>>, Box<dyn Any + Send>);
+
+/// A type-erased task forwarding messages from a
+/// `Box<dyn InputPort<T> + Send>` to an `Arc<dyn OutputPort<T> + Send>`
+/// until the system is shut down, or a `Box<dyn InputPort<()> + Send>`
+/// signals that the receiving end was closed.
+#[cfg(any(feature = "flume", feature = "tokio"))]
+pub(crate) type ErasedForward<S> = fn(
+    Box<dyn Any + Send>,
+    Box<dyn Any + Send>,
+    Box<dyn Any + Send>,
+    S,
+) -> Pin<Box<dyn Future<Output = crate::Result> + Send>>;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A type-erased task sending `Box<T>` messages on a
+/// `Box<dyn OutputPort<T> + Send>`.
+pub(crate) type ErasedSendAll =
+    fn(Box<dyn Any + Send>, Vec<Box<dyn Any + Send>>) -> super::ErasedTask;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A type-erased function wrapping the `Box<dyn OutputPort<T> + Send>`
+/// of an exported input port so that it refuses messages once the given
+/// `Box<dyn OutputPort<T> + Send>` it's forwarded to is disconnected,
+/// returning both, the latter as an `Arc<dyn OutputPort<T> + Send>`.
+pub(crate) type ErasedExportInput =
+    fn(Box<dyn Any + Send>, Box<dyn Any + Send>) -> (Box<dyn Any + Send>, Box<dyn Any + Send>);
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A type-erased function wrapping the `Box<dyn InputPort<T> + Send>` of
+/// an exported input port so that it signals its closing by dropping the
+/// given `Box<dyn OutputPort<()> + Send>`.
+pub(crate) type ErasedForwardInput =
+    fn(Box<dyn Any + Send>, Box<dyn Any + Send>) -> Box<dyn Any + Send>;
+
+#[cfg(any(feature = "flume", feature = "tokio"))]
+/// A type-erased function wrapping a `Box<dyn InputPort<T> + Send>` or a
+/// `Box<dyn OutputPort<T> + Send>` so that it carries the given name and
+/// label.
+pub(crate) type ErasedName =
+    fn(Box<dyn Any + Send>, Option<&'static str>, Option<&'static str>) -> Box<dyn Any + Send>;
+
+/// A type-erased function lending a `Box<dyn InputPort<T> + Send>` or a
+/// `Box<dyn OutputPort<T> + Send>` to a supervised block, returning it to
+/// the given slot once the block is done with it.
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+pub(crate) type ErasedLease = fn(Box<dyn Any + Send>, crate::io::PortSlot) -> Box<dyn Any + Send>;
+
+/// A type-erased task sending a failure event on the
+/// `Box<dyn OutputPort<T> + Send>` in the given slot, if it's there.
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+pub(crate) type ErasedNotifyFailure =
+    fn(crate::io::PortSlot) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A type-erased function checking whether the
+/// `Box<dyn InputPort<T> + Send>` in the given slot is disconnected and has
+/// nothing left to receive, or isn't there anymore.
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+pub(crate) type ErasedIsDrained = fn(&crate::io::PortSlot) -> bool;
+
+/// A type-erased function wrapping `Box<dyn OutputPort<T> + Send>` ports
+/// so that they all divert undeliverable messages to one
+/// `Box<dyn OutputPort<DeadLetter<T>> + Send>`.
+#[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+pub(crate) type ErasedDeadLetter = fn(
+    Vec<Box<dyn Any + Send>>,
+    super::OutputPortId,
+    Box<dyn Any + Send>,
+) -> Vec<Box<dyn Any + Send>>;
+
+/// The message type of a port or connection.
+///
+/// Besides identifying the type, this knows how to instantiate runtime
+/// channels for it, which is what enables a system definition to be
+/// prepared for execution even though its ports are only known by ID.
+#[derive(Clone, Copy)]
+pub struct PortType {
+    id: TypeId,
+    name: &'static str,
+    /// The type ID and name of dead letters of the message type.
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) dead_letter_type: (TypeId, &'static str),
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) send_all: ErasedSendAll,
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) export_input: ErasedExportInput,
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) forward_input: ErasedForwardInput,
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) name_input: ErasedName,
+    #[cfg(any(feature = "flume", feature = "tokio"))]
+    pub(crate) name_output: ErasedName,
+    /// Instantiates a round-robin fan-out policy.
+    #[cfg(feature = "serde")]
+    pub(crate) round_robin: fn() -> super::FanOutPolicy,
+    #[cfg(feature = "tokio")]
+    pub(crate) tokio_channel: fn(usize, usize) -> ErasedChannel,
+    #[cfg(feature = "tokio")]
+    pub(crate) tokio_forward: ErasedForward<crate::tokio::ShutdownHandle>,
+    #[cfg(feature = "flume")]
+    pub(crate) flume_channel: fn(usize, usize) -> ErasedChannel,
+    #[cfg(feature = "flume")]
+    pub(crate) flume_forward: ErasedForward<crate::flume::ShutdownHandle>,
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) lease_input: ErasedLease,
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) lease_output: ErasedLease,
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) notify_failure: ErasedNotifyFailure,
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) is_drained: ErasedIsDrained,
+    #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+    pub(crate) dead_letter: ErasedDeadLetter,
+}
+
+impl PortType {
+    pub fn of<T: Send + 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: type_name::<T>(),
+            #[cfg(any(feature = "std", feature = "serde"))]
+            dead_letter_type: (
+                TypeId::of::<super::DeadLetter<T>>(),
+                type_name::<super::DeadLetter<T>>(),
+            ),
+            #[cfg(any(feature = "flume", feature = "tokio"))]
+            send_all: |output, messages| {
+                use crate::io::OutputPort;
+                let output = output.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                Box::pin(async move {
+                    for message in messages {
+                        output.send(*message.downcast::<T>().unwrap()).await?;
+                    }
+                    Ok(())
+                })
+            },
+            #[cfg(any(feature = "flume", feature = "tokio"))]
+            export_input: |port, peer| {
+                use crate::io::{ExportedInputPort, OutputPort};
+                let port = port.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                let peer = peer.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                let peer: Arc<dyn OutputPort<T> + Send> = Arc::from(*peer);
+                let port: Box<dyn OutputPort<T> + Send> =
+                    Box::new(ExportedInputPort::new(*port, &peer));
+                (Box::new(port), Box::new(peer))
+            },
+            #[cfg(any(feature = "flume", feature = "tokio"))]
+            forward_input: |port, closed| {
+                use crate::io::{ForwardedInputPort, InputPort, OutputPort};
+                let port = port.downcast::<Box<dyn InputPort<T> + Send>>().unwrap();
+                let closed = closed.downcast::<Box<dyn OutputPort<()> + Send>>().unwrap();
+                let port: Box<dyn InputPort<T> + Send> =
+                    Box::new(ForwardedInputPort::new(*port, *closed));
+                Box::new(port)
+            },
+            #[cfg(any(feature = "flume", feature = "tokio"))]
+            name_input: |port, name, label| {
+                use crate::io::{InputPort, NamedInputPort};
+                let port = port.downcast::<Box<dyn InputPort<T> + Send>>().unwrap();
+                let port: Box<dyn InputPort<T> + Send> =
+                    Box::new(NamedInputPort::new(*port, name, label));
+                Box::new(port)
+            },
+            #[cfg(any(feature = "flume", feature = "tokio"))]
+            name_output: |port, name, label| {
+                use crate::io::{NamedOutputPort, OutputPort};
+                let port = port.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                let port: Box<dyn OutputPort<T> + Send> =
+                    Box::new(NamedOutputPort::new(*port, name, label));
+                Box::new(port)
+            },
+            #[cfg(feature = "serde")]
+            round_robin: || crate::io::FanOut::<T>::round_robin().into(),
+            #[cfg(feature = "tokio")]
+            tokio_channel: |buffer, senders| {
+                let (outputs, inputs) = crate::tokio::Channel::<T>::bounded_boxed(buffer, senders);
+                let outputs = outputs.into_iter().map(|port| Box::new(port) as _);
+                (outputs.collect(), Box::new(inputs))
+            },
+            #[cfg(feature = "tokio")]
+            tokio_forward: |input, output, closed, shutdown| {
+                use crate::io::{InputPort, OutputPort};
+                let input = input.downcast::<Box<dyn InputPort<T> + Send>>().unwrap();
+                let output = output.downcast::<Arc<dyn OutputPort<T> + Send>>().unwrap();
+                let closed = closed.downcast::<Box<dyn InputPort<()> + Send>>().unwrap();
+                Box::pin(crate::tokio::forward_until_shutdown(
+                    *input, *output, *closed, shutdown,
+                ))
+            },
+            #[cfg(feature = "flume")]
+            flume_channel: |buffer, senders| {
+                let (outputs, inputs) = crate::flume::Channel::<T>::bounded_boxed(buffer, senders);
+                let outputs = outputs.into_iter().map(|port| Box::new(port) as _);
+                (outputs.collect(), Box::new(inputs))
+            },
+            #[cfg(feature = "flume")]
+            flume_forward: |input, output, closed, shutdown| {
+                use crate::io::{InputPort, OutputPort};
+                let input = input.downcast::<Box<dyn InputPort<T> + Send>>().unwrap();
+                let output = output.downcast::<Arc<dyn OutputPort<T> + Send>>().unwrap();
+                let closed = closed.downcast::<Box<dyn InputPort<()> + Send>>().unwrap();
+                Box::pin(crate::flume::forward_until_shutdown(
+                    *input, *output, *closed, shutdown,
+                ))
+            },
+            #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+            lease_input: |port, slot| {
+                use crate::io::{InputPort, LeasedInputPort};
+                let port = port.downcast::<Box<dyn InputPort<T> + Send>>().unwrap();
+                let port: Box<dyn InputPort<T> + Send> =
+                    Box::new(LeasedInputPort::new(*port, slot));
+                Box::new(port)
+            },
+            #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+            lease_output: |port, slot| {
+                use crate::io::{LeasedOutputPort, OutputPort};
+                let port = port.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                let port: Box<dyn OutputPort<T> + Send> =
+                    Box::new(LeasedOutputPort::new(*port, slot));
+                Box::new(port)
+            },
+            #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+            notify_failure: |slot| {
+                use crate::io::{OutputPort, PortEvent};
+                Box::pin(async move {
+                    let Some(port) = slot.lock().unwrap().take() else {
+                        return;
+                    };
+                    let port = port.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                    let _ = port.send_event(PortEvent::Failure).await;
+                    *slot.lock().unwrap() = Some(port);
+                })
+            },
+            #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+            is_drained: |slot| {
+                use crate::io::InputPort;
+                let slot = slot.lock().unwrap();
+                let Some(port) = slot.as_ref() else {
+                    return true;
+                };
+                let port = port.downcast_ref::<Box<dyn InputPort<T> + Send>>().unwrap();
+                !port.state().is_connected() && port.is_empty()
+            },
+            #[cfg(all(feature = "std", any(feature = "flume", feature = "tokio")))]
+            dead_letter: |ports, source, sink| {
+                use crate::{io::DeadLetterPort, io::OutputPort, model::DeadLetter};
+                let sink = sink
+                    .downcast::<Box<dyn OutputPort<DeadLetter<T>> + Send>>()
+                    .unwrap();
+                let sink: Arc<dyn OutputPort<DeadLetter<T>> + Send> = Arc::from(*sink);
+                let wrap = |port: Box<dyn Any + Send>| -> Box<dyn Any + Send> {
+                    let port = port.downcast::<Box<dyn OutputPort<T> + Send>>().unwrap();
+                    let port: Box<dyn OutputPort<T> + Send> =
+                        Box::new(DeadLetterPort::with_sink(*port, source, Arc::clone(&sink)));
+                    Box::new(port)
+                };
+                ports.into_iter().map(wrap).collect()
+            },
+        }
+    }
+
+    /// Returns the type ID of the message type.
+    pub fn id(&self) -> TypeId {
+        self.id
+    }
+
+    /// Returns the type name of the message type.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl PartialEq for PortType {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for PortType {}
+
+impl core::hash::Hash for PortType {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl From<PortType> for TypeId {
+    fn from(input: PortType) -> Self {
+        input.id
+    }
+}
+
+impl core::fmt::Debug for PortType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl core::fmt::Display for PortType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name)
+    }
+}
@@ -0,0 +1,279 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    Backend, Block, ErasedChannel, ErasedTask, InputPortId, OutputPortId, PortType,
+    SystemDefinition, SystemExports, Tasks,
+};
+use crate::{
+    UNLIMITED,
+    error::Result,
+    io::{
+        ChannelInputs, ChannelOutputs, FailurePolicy, FanOut, InputPort, OutputPort, RestartPolicy,
+        SupervisorHandle,
+    },
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::any::Any;
+
+/// A system of blocks, running as the tasks `B` of a backend, such as a
+/// [`tokio::System`](crate::tokio::System).
+pub struct System<B: Tasks> {
+    /// The system's exported ports and supervisors.
+    pub(crate) exports: SystemExports,
+    pub(crate) blocks: B,
+    pub(crate) failure_policy: FailurePolicy,
+    pub(crate) shutdown: B::ShutdownHandle,
+}
+
+impl<B: Tasks + Default> Default for System<B> {
+    fn default() -> Self {
+        Self::with_tasks(B::default())
+    }
+}
+
+impl<B: Tasks> core::fmt::Debug for System<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("System")
+            .field("exports", &self.exports)
+            .field("blocks", &self.blocks)
+            .field("failure_policy", &self.failure_policy)
+            .field("shutdown", &self.shutdown)
+            .finish()
+    }
+}
+
+impl<B: Tasks + Default> System<B> {
+    /// Builds and executes a system, blocking until completion.
+    pub async fn run<F: FnOnce(&mut Self)>(f: F) -> Result {
+        Self::build(f).execute().await
+    }
+
+    /// Builds a new system.
+    pub fn build<F: FnOnce(&mut Self)>(f: F) -> Self {
+        let mut system = Self::new();
+        f(&mut system);
+        system
+    }
+
+    /// Instantiates a new system.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: Tasks> System<B> {
+    /// Instantiates a new system whose blocks run as the given tasks.
+    pub(crate) fn with_tasks(blocks: B) -> Self {
+        Self {
+            exports: SystemExports::default(),
+            blocks,
+            failure_policy: FailurePolicy::default(),
+            shutdown: B::ShutdownHandle::default(),
+        }
+    }
+
+    /// Sets how this system reacts to the failure of one of its blocks.
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        self.failure_policy = policy;
+    }
+
+    /// Returns how this system reacts to the failure of one of its blocks.
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// Returns a handle for shutting down this system once it's running.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use async_flow::tokio::System;
+    /// # use core::time::Duration;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> async_flow::Result {
+    /// System::run(|s| {
+    ///     s.spawn(core::future::pending());
+    ///     s.shutdown_handle().shutdown(Duration::from_millis(10));
+    /// })
+    /// .await
+    /// # }
+    /// ```
+    pub fn shutdown_handle(&self) -> B::ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    pub fn connect<T>(
+        &mut self,
+        inputs: ChannelInputs<B::Receiver<T>, UNLIMITED>,
+        outputs: ChannelOutputs<B::Sender<T>, UNLIMITED>,
+    ) where
+        T: Send + 'static,
+    {
+        self.spawn(async move {
+            let mut inputs = inputs;
+            let outputs = outputs;
+            while let Some(input) = inputs.recv().await? {
+                outputs.send(input).await?;
+            }
+            Ok(())
+        });
+    }
+
+    /// Distributes the messages received on the given input port over the
+    /// given output ports, according to the given fan-out policy.
+    pub fn fan_out<T>(
+        &mut self,
+        inputs: ChannelInputs<B::Receiver<T>, UNLIMITED>,
+        outputs: Vec<ChannelOutputs<B::Sender<T>, UNLIMITED>>,
+        policy: FanOut<T>,
+    ) where
+        T: Send + 'static,
+    {
+        let outputs = outputs
+            .into_iter()
+            .map(|outputs| Box::new(outputs) as Box<dyn OutputPort<T> + Send>)
+            .collect();
+        self.spawn(crate::io::fan_out(Box::new(inputs), outputs, policy));
+    }
+
+    pub fn spawn<F>(&mut self, task: F) -> B::AbortHandle
+    where
+        F: Future<Output = Result>,
+        F: Send + 'static,
+    {
+        self.blocks.spawn(task)
+    }
+
+    /// Spawns the block created by the given factory, re-creating it
+    /// whenever it completes and the given restart policy calls for it.
+    ///
+    /// The block fails once the policy gives up on restarting it. As its
+    /// ports aren't known here, [`RestartPolicy::Always`] restarts it even
+    /// once its inputs are drained, up to its maximum number of restarts.
+    pub fn spawn_supervised<F, T>(&mut self, policy: RestartPolicy, factory: F) -> SupervisorHandle
+    where
+        F: FnMut() -> T + Send + 'static,
+        T: Future<Output = Result> + Send + 'static,
+    {
+        let supervisor = SupervisorHandle::default();
+        let timer = self.blocks.timer();
+        self.spawn(crate::io::supervise(
+            policy,
+            supervisor.clone(),
+            factory,
+            || false,
+            timer,
+        ));
+        supervisor
+    }
+
+    /// Returns the supervisor of the given block, if it was registered as a
+    /// supervised block of the system definition this system was prepared
+    /// from.
+    pub fn supervisor<T: Block + 'static>(&self, block: &Arc<T>) -> Option<SupervisorHandle> {
+        self.exports.supervisor(block)
+    }
+
+    /// Takes the sending end of an exported input port of this system.
+    ///
+    /// Returns `None` if the port wasn't exported or was already taken.
+    pub fn take_input<T: Send + 'static, const MAX: isize, const MIN: isize>(
+        &mut self,
+        port: &super::Inputs<T, MAX, MIN>,
+    ) -> Option<Box<dyn OutputPort<T> + Send>> {
+        self.take_input_port(port.id())
+    }
+
+    /// Takes the sending end of an exported input port ID of this system,
+    /// such as one of a system loaded from a graph.
+    ///
+    /// Returns `None` if the port wasn't exported, was already taken, or
+    /// has a message type other than `T`.
+    pub fn take_input_port<T: Send + 'static>(
+        &mut self,
+        id: impl Into<InputPortId>,
+    ) -> Option<Box<dyn OutputPort<T> + Send>> {
+        self.exports.take_input(id.into())
+    }
+
+    /// Takes the receiving end of an exported output port of this system.
+    ///
+    /// Returns `None` if the port wasn't exported or was already taken.
+    pub fn take_output<T: Send + 'static, const MAX: isize, const MIN: isize>(
+        &mut self,
+        port: &super::Outputs<T, MAX, MIN>,
+    ) -> Option<Box<dyn InputPort<T> + Send>> {
+        self.take_output_port(port.id())
+    }
+
+    /// Takes the receiving end of an exported output port ID of this system,
+    /// such as one of a system loaded from a graph.
+    ///
+    /// Returns `None` if the port wasn't exported, was already taken, or
+    /// has a message type other than `T`.
+    pub fn take_output_port<T: Send + 'static>(
+        &mut self,
+        id: impl Into<OutputPortId>,
+    ) -> Option<Box<dyn InputPort<T> + Send>> {
+        self.exports.take_output(id.into())
+    }
+
+    /// Waits for all blocks of this system to complete.
+    ///
+    /// Returns the error of the first failing block, including one for a
+    /// panicking block, if the failure policy is
+    /// [`FailurePolicy::FailFast`], in which case the remaining blocks are
+    /// aborted. With [`FailurePolicy::CollectAll`], the remaining blocks run
+    /// to completion and multiple errors are returned as
+    /// [`Error::Aggregate`].
+    ///
+    /// Once shutdown is requested via the system's shutdown handle, the
+    /// blocks are given its grace period to complete, after which any
+    /// remaining ones are aborted.
+    ///
+    /// [`Error::Aggregate`]: crate::Error::Aggregate
+    pub async fn execute(mut self) -> Result {
+        // Exported input ports that weren't taken can't be fed anymore:
+        self.exports.close_inputs();
+        self.blocks
+            .join_all(self.failure_policy, &self.shutdown)
+            .await
+    }
+}
+
+impl<B: Tasks> Backend for System<B> {
+    fn channel(&self, port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel {
+        B::channel(port_type, buffer, senders)
+    }
+
+    fn forward(
+        &self,
+        port_type: PortType,
+        input: Box<dyn Any + Send>,
+        output: Box<dyn Any + Send>,
+        closed: Box<dyn Any + Send>,
+    ) -> ErasedTask {
+        B::forward(port_type, input, output, closed, self.shutdown.clone())
+    }
+
+    fn spawn(&mut self, task: ErasedTask) {
+        self.blocks.spawn(task);
+    }
+
+    fn exports(&mut self) -> &mut SystemExports {
+        &mut self.exports
+    }
+
+    #[cfg(feature = "std")]
+    fn timer(&self) -> crate::io::SharedTimer {
+        self.blocks.timer()
+    }
+}
+
+impl<B: Tasks + Default> From<&SystemDefinition> for System<B> {
+    fn from(system_definition: &SystemDefinition) -> Self {
+        let mut system = Self::new();
+        system_definition.wire(&mut system);
+        system
+    }
+}
@@ -1,11 +1,20 @@
 // This is free and unencumbered software released into the public domain.
 
+#[cfg(feature = "std")]
+use super::DeadLetter;
 use super::{
-    BlockDefinition, InputPortId, Inputs, OutputPortId, Outputs, PortId, PortIdSet,
-    SystemDefinition,
+    Block, BlockHandle, BlockRegistry, FanOutPolicy, InitialPacket, InputPortId, Inputs,
+    OutputPortId, Outputs, PortDefinition, PortId, PortIdSet, PortType, SystemDefinition,
 };
-use alloc::rc::Rc;
-use core::{any::TypeId, fmt::Debug};
+use crate::io::{FanOut, RestartPolicy};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt::Debug;
 use thiserror::Error;
 
 #[derive(Clone, Debug, Error)]
@@ -16,8 +25,88 @@ pub enum SystemBuildError {
     #[error("unregistered output port ID: {0}")]
     UnregisteredOutput(OutputPortId),
 
-    #[error("already connected output port ID: {0}")]
-    AlreadyConnectedOutput(OutputPortId),
+    #[error("already connected output port ID: {0}{name}", name = named(.1))]
+    AlreadyConnectedOutput(OutputPortId, Option<&'static str>),
+
+    #[error("already configured fan-out for output port ID: {0}{name}", name = named(.1))]
+    AlreadyFannedOutOutput(OutputPortId, Option<&'static str>),
+
+    #[error(
+        "under-connected input port ID: {0}{name} ({2} connections, at least {3} required)",
+        name = named(.1)
+    )]
+    UnderconnectedInput(InputPortId, Option<&'static str>, usize, usize),
+
+    #[error(
+        "over-connected input port ID: {0}{name} ({2} connections, at most {3} allowed)",
+        name = named(.1)
+    )]
+    OverconnectedInput(InputPortId, Option<&'static str>, usize, usize),
+
+    #[error(
+        "under-connected output port ID: {0}{name} ({2} connections, at least {3} required)",
+        name = named(.1)
+    )]
+    UnderconnectedOutput(OutputPortId, Option<&'static str>, usize, usize),
+
+    #[error(
+        "over-connected output port ID: {0}{name} ({2} connections, at most {3} allowed)",
+        name = named(.1)
+    )]
+    OverconnectedOutput(OutputPortId, Option<&'static str>, usize, usize),
+
+    #[error("unknown block type: {0}")]
+    UnknownBlockType(String),
+
+    #[error("untyped port ID: {0}")]
+    UntypedPort(PortId),
+
+    #[error(
+        "mismatched message type for port ID: {0}{name} ({2} expected, {3} given)",
+        name = named(.1)
+    )]
+    MismatchedType(PortId, Option<&'static str>, &'static str, &'static str),
+}
+
+impl SystemBuildError {
+    /// Returns the ID of the port that this error concerns, if any.
+    pub fn port(&self) -> Option<PortId> {
+        use SystemBuildError::*;
+        match *self {
+            UnregisteredInput(id) | UnderconnectedInput(id, ..) | OverconnectedInput(id, ..) => {
+                Some(id.into())
+            },
+            UnregisteredOutput(id)
+            | AlreadyConnectedOutput(id, _)
+            | AlreadyFannedOutOutput(id, _)
+            | UnderconnectedOutput(id, ..)
+            | OverconnectedOutput(id, ..) => Some(id.into()),
+            UntypedPort(id) | MismatchedType(id, ..) => Some(id),
+            UnknownBlockType(_) => None,
+        }
+    }
+
+    /// Returns the name of the port that this error concerns, if known.
+    pub fn port_name(&self) -> Option<&'static str> {
+        use SystemBuildError::*;
+        match *self {
+            AlreadyConnectedOutput(_, name)
+            | AlreadyFannedOutOutput(_, name)
+            | UnderconnectedInput(_, name, ..)
+            | OverconnectedInput(_, name, ..)
+            | UnderconnectedOutput(_, name, ..)
+            | OverconnectedOutput(_, name, ..)
+            | MismatchedType(_, name, ..) => name,
+            UnregisteredInput(_) | UnregisteredOutput(_) | UnknownBlockType(_) | UntypedPort(_) => {
+                None
+            },
+        }
+    }
+}
+
+/// Formats a port name, if any, to follow its ID in an error message.
+fn named(name: &Option<&'static str>) -> String {
+    name.map(|name| format!(" {name:?}")).unwrap_or_default()
 }
 
 /// A builder for system definitions.
@@ -29,7 +118,8 @@ pub enum SystemBuildError {
 ///
 /// let mut builder = SystemBuilder::new();
 /// //let block = builder.register(MyBlock::new());
-/// let system = builder.build();
+/// let system = builder.build()?;
+/// # Ok::<(), async_flow::model::SystemBuildError>(())
 /// ```
 #[derive(Clone, Default)]
 pub struct SystemBuilder {
@@ -37,6 +127,9 @@ pub struct SystemBuilder {
     registered_inputs: PortIdSet<InputPortId>,
     registered_outputs: PortIdSet<OutputPortId>,
     connected_outputs: PortIdSet<OutputPortId>,
+    definitions: BTreeMap<PortId, PortDefinition>,
+    /// The system-wide dead-letter input ports, by message type.
+    dead_letter_inputs: Vec<(PortType, InputPortId, PortType)>,
 }
 
 impl SystemBuilder {
@@ -46,18 +139,61 @@ impl SystemBuilder {
     }
 
     /// Registers an instantiated block with the system under construction.
-    pub fn register<T: BlockDefinition + 'static>(&mut self, block: T) -> Rc<T> {
-        let block: Rc<T> = Rc::new(block);
-        self.system.push_block(&block);
+    pub fn register<T: Block + 'static>(&mut self, block: T) -> Arc<T> {
+        self.register_block(block, RestartPolicy::Never)
+    }
+
+    /// Registers an instantiated block with the system under construction,
+    /// to be restarted according to the given policy whenever it completes.
+    ///
+    /// A restarted block is re-executed with the same, still connected,
+    /// runtime ports.
+    #[cfg(feature = "std")]
+    pub fn register_supervised<T: Block + 'static>(
+        &mut self,
+        block: T,
+        policy: RestartPolicy,
+    ) -> Arc<T> {
+        self.register_block(block, policy)
+    }
 
+    fn register_block<T: Block + 'static>(&mut self, block: T, policy: RestartPolicy) -> Arc<T> {
+        let block: Arc<T> = Arc::new(block);
+        let handle = BlockHandle::new(Arc::clone(&block) as _).with_restart_policy(policy);
+        self.register_handle(handle);
+        block
+    }
+
+    /// Registers a type-erased block with the system under construction.
+    pub fn register_handle(&mut self, block: BlockHandle) {
+        for port in block.ports() {
+            self.definitions.insert(port.id, port);
+        }
         for input in block.inputs() {
             self.register_input(input);
         }
         for output in block.outputs() {
             self.register_output(output);
         }
+        self.system.blocks.push(block);
+    }
 
-        block
+    /// Instantiates and registers a block of the given type name from the
+    /// given registry.
+    ///
+    /// Returns a handle to the block, whose port IDs can be connected with
+    /// [`connect_by_id`](Self::connect_by_id).
+    pub fn instantiate(
+        &mut self,
+        registry: &BlockRegistry,
+        r#type: &str,
+    ) -> Result<BlockHandle, SystemBuildError> {
+        let block = registry
+            .instantiate(r#type)
+            .ok_or_else(|| SystemBuildError::UnknownBlockType(r#type.into()))?;
+        let block = BlockHandle::new(block).with_type_name(r#type);
+        self.register_handle(block.clone());
+        Ok(block)
     }
 
     /// Registers an input or output port with the system under construction.
@@ -84,7 +220,7 @@ impl SystemBuilder {
     /// construction.
     pub fn export(
         &mut self,
-        input: impl Into<(PortId, TypeId)>,
+        input: impl Into<(PortId, PortType)>,
     ) -> Result<PortId, SystemBuildError> {
         self.export_port(input)
     }
@@ -93,12 +229,12 @@ impl SystemBuilder {
     /// construction.
     pub fn export_port(
         &mut self,
-        input: impl Into<(PortId, TypeId)>,
+        input: impl Into<(PortId, PortType)>,
     ) -> Result<PortId, SystemBuildError> {
-        let (input, type_id) = input.into();
+        let (input, port_type) = input.into();
         match input {
-            PortId::Input(input) => self.export_input((input, type_id)).map(|_| ()),
-            PortId::Output(output) => self.export_output((output, type_id)).map(|_| ()),
+            PortId::Input(input) => self.export_input((input, port_type)).map(|_| ()),
+            PortId::Output(output) => self.export_output((output, port_type)).map(|_| ()),
         }?;
         Ok(input)
     }
@@ -106,26 +242,37 @@ impl SystemBuilder {
     /// Exports an input port registered with the system under construction.
     pub fn export_input(
         &mut self,
-        input: impl Into<(InputPortId, TypeId)>,
+        input: impl Into<(InputPortId, PortType)>,
     ) -> Result<InputPortId, SystemBuildError> {
-        let (input, type_id) = input.into();
+        let (input, port_type) = input.into();
         if !self.registered_inputs.contains(input) {
             return Err(SystemBuildError::UnregisteredInput(input));
         }
-        self.system.inputs.insert(input, type_id);
+        self.system.inputs.insert(input, port_type);
         Ok(input)
     }
 
     /// Exports an output port registered with the system under construction.
+    ///
+    /// Exporting an output port counts as connecting it, so an output port
+    /// can only be both exported and connected if it's fanned out.
     pub fn export_output(
         &mut self,
-        output: impl Into<(OutputPortId, TypeId)>,
+        output: impl Into<(OutputPortId, PortType)>,
     ) -> Result<OutputPortId, SystemBuildError> {
-        let (output, type_id) = output.into();
+        let (output, port_type) = output.into();
         if !self.registered_outputs.contains(output) {
             return Err(SystemBuildError::UnregisteredOutput(output));
         }
-        self.system.outputs.insert(output, type_id);
+        if self.system.outputs.contains(output) {
+            return Ok(output); // already exported
+        }
+        if self.connected_outputs.contains(output) && !self.system.fan_outs.contains_key(&output) {
+            let name = self.port_name(output);
+            return Err(SystemBuildError::AlreadyConnectedOutput(output, name));
+        }
+        self.connected_outputs.insert(output);
+        self.system.outputs.insert(output, port_type);
         Ok(output)
     }
 
@@ -133,12 +280,212 @@ impl SystemBuilder {
     ///
     /// Returns a boolean indicating whether the connection was newly
     /// inserted or already existed.
-    pub fn connect<T: 'static>(
+    pub fn connect<
+        T: Send + 'static,
+        const OMAX: isize,
+        const OMIN: isize,
+        const IMAX: isize,
+        const IMIN: isize,
+    >(
+        &mut self,
+        output: &Outputs<T, OMAX, OMIN>,
+        input: &Inputs<T, IMAX, IMIN>,
+    ) -> Result<bool, SystemBuildError> {
+        self.connect_ports(output.id(), input.id(), PortType::of::<T>())
+    }
+
+    /// Connects an output port to an input port of the same type, with the
+    /// given fan-out policy for the connection.
+    ///
+    /// The policy distributes messages over all connections of the output
+    /// port, so connecting it again requires a policy of the same kind.
+    pub fn connect_with<
+        T: Send + 'static,
+        const OMAX: isize,
+        const OMIN: isize,
+        const IMAX: isize,
+        const IMIN: isize,
+    >(
         &mut self,
-        output: &Outputs<T>,
-        input: &Inputs<T>,
+        output: &Outputs<T, OMAX, OMIN>,
+        input: &Inputs<T, IMAX, IMIN>,
+        policy: FanOut<T>,
     ) -> Result<bool, SystemBuildError> {
-        self.connect_ports(output.id(), input.id(), TypeId::of::<T>())
+        let id = output.id();
+        match self.system.fan_outs.get(&id) {
+            Some(existing) if existing.kind() != policy.kind() => {
+                let name = self.port_name(id);
+                return Err(SystemBuildError::AlreadyFannedOutOutput(id, name));
+            },
+            Some(_) => return self.connect(output, input),
+            None => self.fan_out(output, policy)?,
+        }
+        self.connect(output, input).inspect_err(|_| {
+            self.system.fan_outs.remove(&id);
+        })
+    }
+
+    /// Connects an output port ID to an input port ID, such as those of a
+    /// block instantiated from a [`BlockRegistry`].
+    ///
+    /// Returns an error if either port lacks a [`PortDefinition`], or if
+    /// their message types differ.
+    pub fn connect_by_id(
+        &mut self,
+        output: impl Into<OutputPortId>,
+        input: impl Into<InputPortId>,
+    ) -> Result<bool, SystemBuildError> {
+        let (output, input) = (output.into(), input.into());
+        let port_type = |id: PortId| match self.definitions.get(&id) {
+            Some(port) => Ok(port.r#type),
+            None => Err(SystemBuildError::UntypedPort(id)),
+        };
+        let port_type = port_type(output.into())?;
+        self.connect_ports(output, input, port_type)
+    }
+
+    /// Sets the fan-out policy of an output port, which allows it to be
+    /// connected to multiple input ports.
+    pub fn fan_out<T: Send + 'static, const MAX: isize, const MIN: isize>(
+        &mut self,
+        output: &Outputs<T, MAX, MIN>,
+        policy: FanOut<T>,
+    ) -> Result<(), SystemBuildError> {
+        self.fan_out_port(output.id(), policy.into())
+    }
+
+    /// Sets the fan-out policy of an output port ID.
+    /// This isn't public because it doesn't enforce type safety.
+    pub(crate) fn fan_out_port(
+        &mut self,
+        output: impl Into<OutputPortId>,
+        policy: FanOutPolicy,
+    ) -> Result<(), SystemBuildError> {
+        let output = output.into();
+        if !self.registered_outputs.contains(output) {
+            return Err(SystemBuildError::UnregisteredOutput(output));
+        }
+        if self.system.fan_outs.contains_key(&output) {
+            let name = self.port_name(output);
+            return Err(SystemBuildError::AlreadyFannedOutOutput(output, name));
+        }
+        self.system.fan_outs.insert(output, policy);
+        Ok(())
+    }
+
+    /// Routes the messages an output port can't deliver, because its peer is
+    /// disconnected or closed, to a dead-letter input port.
+    ///
+    /// This takes precedence over a system-wide dead-letter input port set
+    /// with [`dead_letter`](Self::dead_letter).
+    #[cfg(feature = "std")]
+    pub fn connect_dead_letter<
+        T: Send + 'static,
+        const OMAX: isize,
+        const OMIN: isize,
+        const IMAX: isize,
+        const IMIN: isize,
+    >(
+        &mut self,
+        output: &Outputs<T, OMAX, OMIN>,
+        input: &Inputs<DeadLetter<T>, IMAX, IMIN>,
+    ) -> Result<(), SystemBuildError> {
+        let port_type = PortType::of::<DeadLetter<T>>();
+        self.connect_dead_letter_port(output.id(), input.id(), port_type)
+    }
+
+    /// Routes the messages an output port ID can't deliver to a dead-letter
+    /// input port ID of the given type, which must be the type of dead
+    /// letters of the output port's message type.
+    /// This isn't public because it doesn't enforce type safety.
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn connect_dead_letter_port(
+        &mut self,
+        output: impl Into<OutputPortId>,
+        input: impl Into<InputPortId>,
+        port_type: PortType,
+    ) -> Result<(), SystemBuildError> {
+        let (output, input) = (output.into(), input.into());
+        if !self.registered_inputs.contains(input) {
+            return Err(SystemBuildError::UnregisteredInput(input));
+        }
+        if !self.registered_outputs.contains(output) {
+            return Err(SystemBuildError::UnregisteredOutput(output));
+        }
+        let message_type = self.definitions.get(&output.into()).map(|port| port.r#type);
+        let mismatched = message_type.filter(|typ| typ.dead_letter_type.0 != port_type.id());
+        if let Some(message_type) = mismatched {
+            let (expected, given) = (message_type.dead_letter_type.1, port_type.name());
+            let name = self.port_name(input);
+            return Err(SystemBuildError::MismatchedType(
+                input.into(),
+                name,
+                expected,
+                given,
+            ));
+        }
+        self.system.dead_letters.insert(output, (input, port_type));
+        Ok(())
+    }
+
+    /// Routes the messages any output port of the message type `T` can't
+    /// deliver, because its peer is disconnected or closed, to a dead-letter
+    /// input port.
+    #[cfg(feature = "std")]
+    pub fn dead_letter<T: Send + 'static, const MAX: isize, const MIN: isize>(
+        &mut self,
+        input: &Inputs<DeadLetter<T>, MAX, MIN>,
+    ) -> Result<(), SystemBuildError> {
+        let input = input.id();
+        if !self.registered_inputs.contains(input) {
+            return Err(SystemBuildError::UnregisteredInput(input));
+        }
+        let message_type = PortType::of::<T>();
+        self.dead_letter_inputs
+            .retain(|(port_type, _, _)| *port_type != message_type);
+        self.dead_letter_inputs
+            .push((message_type, input, PortType::of::<DeadLetter<T>>()));
+        Ok(())
+    }
+
+    /// Sends a message to an input port as soon as the system starts.
+    pub fn initial_packet<T, const MAX: isize, const MIN: isize>(
+        &mut self,
+        input: &Inputs<T, MAX, MIN>,
+        message: T,
+    ) -> Result<(), SystemBuildError>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.initial_packet_port(input.id(), InitialPacket::new(message))
+    }
+
+    /// Sends a type-erased message to an input port ID as soon as the system
+    /// starts.
+    ///
+    /// Returns an error if the port is defined with a different message type.
+    pub fn initial_packet_port(
+        &mut self,
+        input: impl Into<InputPortId>,
+        packet: InitialPacket,
+    ) -> Result<(), SystemBuildError> {
+        let input = input.into();
+        if !self.registered_inputs.contains(input) {
+            return Err(SystemBuildError::UnregisteredInput(input));
+        }
+        let port_type = packet.port_type();
+        let mismatched = self.definitions.get(&input.into());
+        if let Some(port) = mismatched.filter(|port| port.r#type != port_type) {
+            let (expected, given) = (port.r#type.name(), port_type.name());
+            return Err(SystemBuildError::MismatchedType(
+                input.into(),
+                port.name,
+                expected,
+                given,
+            ));
+        }
+        self.system.initial_packets.push((input, packet));
+        Ok(())
     }
 
     /// Connects an output port ID to an input port ID.
@@ -150,7 +497,7 @@ impl SystemBuilder {
         &mut self,
         output: impl Into<OutputPortId>,
         input: impl Into<InputPortId>,
-        type_id: TypeId,
+        port_type: PortType,
     ) -> Result<bool, SystemBuildError> {
         let output = output.into();
         let input = input.into();
@@ -160,24 +507,107 @@ impl SystemBuilder {
         if !self.registered_outputs.contains(output) {
             return Err(SystemBuildError::UnregisteredOutput(output));
         }
-        if self.connected_outputs.contains(output) {
-            return Err(SystemBuildError::AlreadyConnectedOutput(output));
+        if self.connected_outputs.contains(output) && !self.system.fan_outs.contains_key(&output) {
+            let name = self.port_name(output);
+            return Err(SystemBuildError::AlreadyConnectedOutput(output, name));
+        }
+        for id in [PortId::from(output), input.into()] {
+            let mismatched = self.definitions.get(&id);
+            if let Some(port) = mismatched.filter(|port| port.r#type != port_type) {
+                let (expected, given) = (port.r#type.name(), port_type.name());
+                let name = port.name;
+                return Err(SystemBuildError::MismatchedType(id, name, expected, given));
+            }
         }
         let result = self
             .system
             .connections
-            .insert((output, input), type_id)
+            .insert((output, input), port_type)
             .is_none();
         if result {
-            // Output ports can only be connected once:
+            // Output ports can only be connected once, unless fanned out:
             self.connected_outputs.insert(output);
         }
         Ok(result)
     }
 
+    /// Returns the name of a defined port, if any.
+    fn port_name(&self, id: impl Into<PortId>) -> Option<&'static str> {
+        self.definitions.get(&id.into()).and_then(|port| port.name)
+    }
+
     /// Builds the system under construction.
-    pub fn build(self) -> SystemDefinition {
-        self.system
+    ///
+    /// Returns an error if any port defined by a registered block is
+    /// connected fewer or more times than its cardinality bounds allow.
+    /// Exporting a port counts as connecting it.
+    ///
+    /// Only ports returned by [`BlockDefinition::ports`](super::BlockDefinition::ports)
+    /// carry cardinality bounds, so ports that a block only lists in its
+    /// [`inputs`](super::BlockDefinition::inputs) or
+    /// [`outputs`](super::BlockDefinition::outputs) aren't validated.
+    pub fn build(mut self) -> Result<SystemDefinition, SystemBuildError> {
+        self.resolve_dead_letters();
+        self.validate()?;
+        Ok(self.system)
+    }
+
+    /// Routes the output ports of each message type with a system-wide
+    /// dead-letter input port to it, unless they have their own.
+    fn resolve_dead_letters(&mut self) {
+        let connections = self.system.connections.iter();
+        let outputs = connections.map(|(&(output, _), &port_type)| (output, port_type));
+        let outputs = outputs
+            .chain(self.system.outputs.iter().map(|(&id, &typ)| (id, typ)))
+            .collect::<Vec<_>>();
+        for (message_type, input, port_type) in &self.dead_letter_inputs {
+            for (output, _) in outputs.iter().filter(|(_, typ)| typ == message_type) {
+                self.system
+                    .dead_letters
+                    .entry(*output)
+                    .or_insert((*input, *port_type));
+            }
+        }
+    }
+
+    /// Validates the connection count of each defined port against its
+    /// cardinality bounds.
+    fn validate(&self) -> Result<(), SystemBuildError> {
+        let mut counts = BTreeMap::<PortId, usize>::new();
+        let exports = self.system.inputs.iter().map(|(&id, _)| PortId::from(id));
+        let exports = exports.chain(self.system.outputs.iter().map(|(&id, _)| id.into()));
+        let connections = self.system.connections.keys();
+        let connections = connections.flat_map(|&(output, input)| [output.into(), input.into()]);
+        let dead_letters = self.system.dead_letters.values().map(|&(id, _)| id.into());
+        // Any number of initial packets for an input port count as one connection:
+        let packets = self.system.initial_packets.iter().map(|&(id, _)| id);
+        let packets = packets
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(PortId::from);
+        for id in exports
+            .chain(connections)
+            .chain(dead_letters)
+            .chain(packets)
+        {
+            *counts.entry(id).or_default() += 1;
+        }
+
+        use SystemBuildError::*;
+        for (id, port) in &self.definitions {
+            let count = counts.get(id).copied().unwrap_or_default();
+            if port.allows(count) {
+                continue;
+            }
+            let (name, min, max) = (port.name, port.min, port.max.unwrap_or(usize::MAX));
+            return Err(match (*id, count < min) {
+                (PortId::Input(id), true) => UnderconnectedInput(id, name, count, min),
+                (PortId::Input(id), false) => OverconnectedInput(id, name, count, max),
+                (PortId::Output(id), true) => UnderconnectedOutput(id, name, count, min),
+                (PortId::Output(id), false) => OverconnectedOutput(id, name, count, max),
+            });
+        }
+        Ok(())
     }
 }
 
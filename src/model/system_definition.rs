@@ -1,16 +1,31 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::{BlockDefinition, InputPortId, OutputPortId, PortIdMap, PortIdSet, SystemBuilder};
-use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
-use core::{any::TypeId, fmt::Debug, ops::RangeInclusive};
+use super::{
+    Block, BlockInputs, BlockOutputs, FanOutPolicy, InitialPacket, InputPortId, OutputPortId,
+    PortDefinition, PortId, PortIdMap, PortIdSet, PortType, SystemBuilder,
+};
+use crate::{error::Result, io::RestartPolicy};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{fmt::Debug, ops::RangeInclusive};
 
 /// A system definition.
 #[derive(Clone, Default)]
 pub struct SystemDefinition {
-    pub inputs: PortIdMap<InputPortId, TypeId>,
-    pub outputs: PortIdMap<OutputPortId, TypeId>,
+    pub inputs: PortIdMap<InputPortId, PortType>,
+    pub outputs: PortIdMap<OutputPortId, PortType>,
     pub blocks: Vec<BlockHandle>,
-    pub connections: BTreeMap<(OutputPortId, InputPortId), TypeId>,
+    pub connections: BTreeMap<(OutputPortId, InputPortId), PortType>,
+    pub fan_outs: BTreeMap<OutputPortId, FanOutPolicy>,
+    /// The dead-letter input port of each output port, with its type.
+    pub dead_letters: BTreeMap<OutputPortId, (InputPortId, PortType)>,
+    pub initial_packets: Vec<(InputPortId, InitialPacket)>,
 }
 
 impl SystemDefinition {
@@ -20,12 +35,67 @@ impl SystemDefinition {
     }
 
     /// Prepares this system definition for execution.
+    ///
+    /// This connects the ports of all registered blocks and spawns them,
+    /// so it must be called from within a Tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime; use
+    /// [`prepare_on`](Self::prepare_on) to prepare the system on the
+    /// runtime of a given scheduler instead.
+    #[cfg(feature = "tokio")]
     pub fn prepare(&self) -> crate::tokio::System {
         self.into()
     }
 
-    pub(crate) fn push_block<T: BlockDefinition + 'static>(&mut self, block: &Rc<T>) {
-        self.blocks.push(BlockHandle(Rc::clone(&block) as _));
+    /// Prepares this system definition for execution on the runtime of the
+    /// given scheduler.
+    ///
+    /// See [`System::execute_on`](crate::tokio::System::execute_on).
+    #[cfg(feature = "tokio")]
+    pub fn prepare_on<S>(&self, scheduler: &S) -> crate::tokio::System
+    where
+        S: AsRef<::tokio::runtime::Handle>,
+    {
+        let mut system = crate::tokio::System::new_on(scheduler);
+        self.wire(&mut system);
+        system
+    }
+
+    /// Prepares this system definition for execution.
+    ///
+    /// This connects the ports of all registered blocks and spawns each of
+    /// them on its own thread.
+    #[cfg(all(feature = "flume", not(feature = "tokio")))]
+    pub fn prepare(&self) -> crate::flume::System {
+        self.into()
+    }
+
+    /// Returns the names of the blocks, in order, naming any unnamed ones
+    /// after their type and position.
+    pub fn block_names(&self) -> Vec<String> {
+        let named = self.blocks.iter().filter_map(|block| block.name());
+        let mut names = named.map(String::from).collect::<BTreeSet<_>>();
+        let mut result = Vec::with_capacity(self.blocks.len());
+        for (index, block) in self.blocks.iter().enumerate() {
+            let name = match block.name() {
+                Some(name) => name.into(),
+                None => {
+                    let prefix = block.type_name().to_lowercase();
+                    let mut index = index;
+                    let mut name = format!("{prefix}{index}");
+                    while names.contains(&name) {
+                        index += 1;
+                        name = format!("{prefix}{index}");
+                    }
+                    names.insert(name.clone());
+                    name
+                },
+            };
+            result.push(name);
+        }
+        result
     }
 
     pub fn inputs_min(&self) -> Option<InputPortId> {
@@ -44,7 +114,7 @@ impl SystemDefinition {
             .collect::<Vec<_>>();
         let min = ranges.iter().map(|r| *r.start()).min()?;
         let max = ranges.iter().map(|r| *r.end()).max()?;
-        Some(min.into()..=max.into())
+        Some(min..=max)
     }
 
     pub fn outputs_min(&self) -> Option<OutputPortId> {
@@ -63,7 +133,7 @@ impl SystemDefinition {
             .collect::<Vec<_>>();
         let min = ranges.iter().map(|r| *r.start()).min()?;
         let max = ranges.iter().map(|r| *r.end()).max()?;
-        Some(min.into()..=max.into())
+        Some(min..=max)
     }
 }
 
@@ -95,27 +165,142 @@ impl Debug for SystemDefinition {
                     .map(|((from, to), typ)| ((from.0, to.0), typ))
                     .collect::<Vec<_>>(),
             )
+            .field(
+                "fan_outs",
+                &self
+                    .fan_outs
+                    .iter()
+                    .map(|(id, policy)| (id.0, policy.kind()))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "dead_letters",
+                &self
+                    .dead_letters
+                    .iter()
+                    .map(|(from, (to, typ))| ((from.0, to.0), typ))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "initial_packets",
+                &self
+                    .initial_packets
+                    .iter()
+                    .map(|(id, packet)| (id.0, packet.port_type()))
+                    .collect::<Vec<_>>(),
+            )
             .finish()
     }
 }
 
+/// A block registered with a system definition.
 #[derive(Clone)]
-pub struct BlockHandle(Rc<dyn BlockDefinition>);
+pub struct BlockHandle {
+    block: Arc<dyn Block>,
+    name: Option<String>,
+    /// The name the block type was registered under, if instantiated by it.
+    type_name: Option<String>,
+    restart_policy: RestartPolicy,
+}
 
 impl BlockHandle {
+    pub fn new(block: Arc<dyn Block>) -> Self {
+        Self {
+            block,
+            name: None,
+            type_name: None,
+            restart_policy: RestartPolicy::default(),
+        }
+    }
+
+    /// Sets the name of this block instance, which distinguishes it from
+    /// other instances of the same block type.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the name that the block type was registered under, such as
+    /// with [`BlockType::with_name`](super::BlockType::with_name).
+    pub(crate) fn with_type_name(mut self, type_name: impl Into<String>) -> Self {
+        self.type_name = Some(type_name.into());
+        self
+    }
+
+    /// Sets how the block is restarted once it completes.
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Returns the name of this block instance, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the name of the block type, as registered if the block was
+    /// instantiated from a registry or graph loader.
+    pub fn type_name(&self) -> Cow<'_, str> {
+        match &self.type_name {
+            Some(type_name) => Cow::Borrowed(type_name),
+            None => self.block.name(),
+        }
+    }
+
+    /// Returns how the block is restarted once it completes.
+    pub fn restart_policy(&self) -> RestartPolicy {
+        self.restart_policy
+    }
+
+    /// Checks whether this is a handle for the given block.
+    pub fn is<T: Block + 'static>(&self, block: &Arc<T>) -> bool {
+        core::ptr::addr_eq(Arc::as_ptr(&self.block), Arc::as_ptr(block))
+    }
+
+    /// Executes the block with the given runtime ports.
+    pub fn execute(
+        &self,
+        inputs: BlockInputs,
+        outputs: BlockOutputs,
+    ) -> impl Future<Output = Result> + Send + 'static + use<> {
+        let block = Arc::clone(&self.block);
+        async move { block.execute(inputs, outputs).await }
+    }
+
     pub fn inputs(&self) -> Vec<InputPortId> {
-        self.0.inputs()
+        self.block.inputs()
     }
 
     pub fn outputs(&self) -> Vec<OutputPortId> {
-        self.0.outputs()
+        self.block.outputs()
+    }
+
+    pub fn ports(&self) -> Vec<PortDefinition> {
+        self.block.ports()
+    }
+
+    /// Returns how each port of the block is referred to: by its name, if
+    /// defined, or else by its index among the block's input or output ports.
+    pub(crate) fn port_names(&self) -> Vec<(PortId, String)> {
+        let names = self
+            .ports()
+            .into_iter()
+            .filter_map(|port| Some((port.id, port.name?)));
+        let names = names.collect::<BTreeMap<_, _>>();
+        let inputs = self.inputs().into_iter().map(PortId::from).enumerate();
+        let outputs = self.outputs().into_iter().map(PortId::from).enumerate();
+        inputs
+            .chain(outputs)
+            .map(|(index, id)| match names.get(&id) {
+                Some(name) => (id, String::from(*name)),
+                None => (id, index.to_string()),
+            })
+            .collect()
     }
 
     pub fn inputs_range(&self) -> Option<RangeInclusive<isize>> {
-        let inputs = self.0.inputs();
-        let Some(&min) = inputs.iter().min() else {
-            return None;
-        };
+        let inputs = self.block.inputs();
+        let &min = inputs.iter().min()?;
         let Some(&max) = inputs.iter().max() else {
             unreachable!()
         };
@@ -123,10 +308,8 @@ impl BlockHandle {
     }
 
     pub fn outputs_range(&self) -> Option<RangeInclusive<isize>> {
-        let outputs = self.0.outputs();
-        let Some(&min) = outputs.iter().min() else {
-            return None;
-        };
+        let outputs = self.block.outputs();
+        let &min = outputs.iter().min()?;
         let Some(&max) = outputs.iter().max() else {
             unreachable!()
         };
@@ -136,11 +319,16 @@ impl BlockHandle {
 
 impl Debug for BlockHandle {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let inputs = PortIdSet::from(&self.0.inputs());
-        let outputs = PortIdSet::from(&self.0.outputs());
-        f.debug_struct(&self.0.name())
-            .field("inputs", &inputs)
-            .field("outputs", &outputs)
-            .finish()
+        let inputs = PortIdSet::from(&self.block.inputs());
+        let outputs = PortIdSet::from(&self.block.outputs());
+        let names = self.ports().into_iter();
+        let names = names.filter_map(|port| Some((port.id, port.name?)));
+        let names = names.collect::<BTreeMap<_, _>>();
+        let mut f = f.debug_struct(&self.block.name());
+        f.field("inputs", &inputs).field("outputs", &outputs);
+        if !names.is_empty() {
+            f.field("names", &names);
+        }
+        f.finish()
     }
 }
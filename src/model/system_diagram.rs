@@ -0,0 +1,233 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{PortId, PortType, SystemDefinition};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+impl SystemDefinition {
+    /// Renders this system definition as a Graphviz DOT digraph.
+    ///
+    /// Blocks are rendered as records with their input ports on the left
+    /// and their output ports on the right, connections as edges labeled
+    /// with their message type, and dead-letter connections as dashed edges.
+    /// Exported ports and initial packets are rendered as points, and ports
+    /// that don't belong to any block as nodes named after their ID, all
+    /// with IDs prefixed with `__` to tell them apart from blocks.
+    pub fn to_dot(&self) -> String {
+        let ports = self.port_labels();
+        let mut output = String::from("digraph {\n    rankdir=LR;\n    node [shape=record];\n");
+        for (block, name) in self.blocks.iter().zip(self.block_names()) {
+            let fields = |ids: Vec<PortId>, prefix: char| {
+                let fields = ids.iter().enumerate().map(|(index, id)| {
+                    let label = ports
+                        .get(id)
+                        .map_or_else(|| id.to_string(), |port| port.1.clone());
+                    format!("<{prefix}{index}> {}", escape_record(&label))
+                });
+                fields.collect::<Vec<_>>().join("|")
+            };
+            let inputs = fields(block.inputs().into_iter().map(PortId::from).collect(), 'i');
+            let outputs = fields(block.outputs().into_iter().map(PortId::from).collect(), 'o');
+            let title = escape_record(&format!("{name}: {}", block.type_name()));
+            let label = match (inputs.is_empty(), outputs.is_empty()) {
+                (true, true) => title,
+                (false, true) => format!("{{{inputs}}}|{title}"),
+                (true, false) => format!("{title}|{{{outputs}}}"),
+                (false, false) => format!("{{{inputs}}}|{title}|{{{outputs}}}"),
+            };
+            writeln!(output, "    {} [label=\"{{{label}}}\"];", quote(&name)).unwrap();
+        }
+
+        let port = |id: PortId| match ports.get(&id) {
+            Some((block, _, field)) => format!("{}:{field}", quote(block)),
+            None => quote(&format!("__port {id}")),
+        };
+        let edge = |output: &mut String, from: &str, to: &str, label: &str, dashed: bool| {
+            let style = if dashed { ", style=dashed" } else { "" };
+            let label = escape(label);
+            writeln!(output, "    {from} -> {to} [label=\"{label}\"{style}];").unwrap();
+        };
+        for (&(from, to), port_type) in &self.connections {
+            let label = short_type_name(port_type.name());
+            edge(
+                &mut output,
+                &port(from.into()),
+                &port(to.into()),
+                &label,
+                false,
+            );
+        }
+        for (&from, &(to, port_type)) in &self.dead_letters {
+            let label = short_type_name(port_type.name());
+            edge(
+                &mut output,
+                &port(from.into()),
+                &port(to.into()),
+                &label,
+                true,
+            );
+        }
+        for (index, (kind, id, port_type)) in self.endpoints().into_iter().enumerate() {
+            let point = quote(&format!("__{kind}{index}"));
+            writeln!(output, "    {point} [shape=point];").unwrap();
+            let label = short_type_name(port_type.name());
+            match kind {
+                "output" => edge(&mut output, &port(id), &point, &label, false),
+                _ => edge(&mut output, &point, &port(id), &label, false),
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Renders this system definition as a Mermaid flowchart.
+    ///
+    /// Blocks are rendered as nodes, and connections as edges labeled with
+    /// their ports and message type, with dead-letter connections dotted.
+    /// Exported ports and initial packets are rendered as small circles,
+    /// and ports that don't belong to any block as nodes named after their ID.
+    pub fn to_mermaid(&self) -> String {
+        let ports = self.port_labels();
+        let names = self.block_names();
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), format!("block{index}")))
+            .collect::<BTreeMap<_, _>>();
+        let mut output = String::from("flowchart LR\n");
+        for (block, name) in self.blocks.iter().zip(&names) {
+            let label = escape_mermaid(&format!("{name}: {}", block.type_name()));
+            writeln!(output, "    {}[\"{label}\"]", ids[name.as_str()]).unwrap();
+        }
+
+        // Returns the node and label of a port:
+        let port = |id: PortId| match ports.get(&id) {
+            Some((block, label, _)) => (ids[block.as_str()].clone(), label.clone()),
+            None => (format!("port{id}").replace('-', "_"), id.to_string()),
+        };
+        let edge = |output: &mut String, from: &str, to: &str, label: &str, arrow: &str| {
+            let label = escape_mermaid(label);
+            writeln!(output, "    {from} {arrow}|\"{label}\"| {to}").unwrap();
+        };
+        let connections = self
+            .connections
+            .iter()
+            .map(|(&(from, to), &typ)| (from, to, typ, "-->"));
+        let dead_letters = self.dead_letters.iter();
+        let dead_letters = dead_letters.map(|(&from, &(to, typ))| (from, to, typ, "-.->"));
+        for (from, to, port_type, arrow) in connections.chain(dead_letters) {
+            let ((from, from_label), (to, to_label)) = (port(from.into()), port(to.into()));
+            let port_type = short_type_name(port_type.name());
+            let label = format!("{from_label} → {to_label}: {port_type}");
+            edge(&mut output, &from, &to, &label, arrow);
+        }
+        for (index, (kind, id, port_type)) in self.endpoints().into_iter().enumerate() {
+            let point = format!("{kind}{index}");
+            writeln!(output, "    {point}(( ))").unwrap();
+            let (block, port) = port(id);
+            let label = format!("{port}: {}", short_type_name(port_type.name()));
+            match kind {
+                "output" => edge(&mut output, &block, &point, &label, "-->"),
+                _ => edge(&mut output, &point, &block, &label, "-->"),
+            }
+        }
+        output
+    }
+
+    /// Returns the exported input ports, exported output ports, and initial
+    /// packets, each with its kind and message type.
+    fn endpoints(&self) -> Vec<(&'static str, PortId, PortType)> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(&id, &typ)| ("input", id.into(), typ));
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|(&id, &typ)| ("output", id.into(), typ));
+        let packets = self.initial_packets.iter();
+        let packets = packets.map(|(id, packet)| ("packet", (*id).into(), packet.port_type()));
+        inputs.chain(outputs).chain(packets).collect()
+    }
+
+    /// Returns the block name, label, and record field of each port.
+    ///
+    /// Ports are labeled with their label, if defined, or else with how
+    /// they are referred to in system graphs, by name or index.
+    fn port_labels(&self) -> BTreeMap<PortId, (String, String, String)> {
+        let mut ports = BTreeMap::new();
+        for (block, name) in self.blocks.iter().zip(self.block_names()) {
+            let labels = block.ports().into_iter();
+            let labels = labels.filter_map(|port| Some((port.id, port.label?)));
+            let labels = labels.collect::<BTreeMap<_, _>>();
+            let mut counts = [0, 0]; // of input and output ports
+            for (id, port) in block.port_names() {
+                let (prefix, count) = match id {
+                    PortId::Input(_) => ('i', &mut counts[0]),
+                    PortId::Output(_) => ('o', &mut counts[1]),
+                };
+                let field = format!("{prefix}{count}");
+                *count += 1;
+                let label = labels.get(&id).map_or(port, |label| String::from(*label));
+                ports.insert(id, (name.clone(), label, field));
+            }
+        }
+        ports
+    }
+}
+
+/// Strips the module paths from a type name, turning for example
+/// `alloc::vec::Vec<alloc::string::String>` into `Vec<String>`.
+fn short_type_name(name: &str) -> String {
+    let mut output = String::new();
+    let mut start = 0; // of the current path
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.next_if_eq(&':').is_some() {
+            output.truncate(start);
+            continue;
+        }
+        output.push(c);
+        if !(c.is_alphanumeric() || c == '_') {
+            start = output.len();
+        }
+    }
+    output
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", escape(id))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_record(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output
+}
+
+fn escape_mermaid(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("#quot;"),
+            '<' => output.push_str("#lt;"),
+            '>' => output.push_str("#gt;"),
+            c => output.push(c),
+        }
+    }
+    output
+}
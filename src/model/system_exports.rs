@@ -0,0 +1,91 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Block, BlockHandle, InputPortId, OutputPortId};
+use crate::io::{InputPort, OutputPort, SupervisorHandle};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use core::any::Any;
+
+/// The runtime ports exported by a system prepared from a system definition,
+/// and the supervisors of its supervised blocks, on any backend.
+#[derive(Default)]
+pub(crate) struct SystemExports {
+    /// The sending ends of the system's exported input ports.
+    inputs: BTreeMap<InputPortId, Box<dyn Any + Send>>,
+    /// The receiving ends of the system's exported output ports.
+    outputs: BTreeMap<OutputPortId, Box<dyn Any + Send>>,
+    /// The supervisors of the system's supervised blocks.
+    supervisors: Vec<(BlockHandle, SupervisorHandle)>,
+}
+
+impl core::fmt::Debug for SystemExports {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SystemExports")
+            .field("inputs", &self.inputs.keys().collect::<Vec<_>>())
+            .field("outputs", &self.outputs.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SystemExports {
+    /// Registers the sending end of an exported input port.
+    pub(crate) fn export_input(&mut self, id: InputPortId, port: Box<dyn Any + Send>) {
+        self.inputs.insert(id, port);
+    }
+
+    /// Registers the receiving end of an exported output port.
+    pub(crate) fn export_output(&mut self, id: OutputPortId, port: Box<dyn Any + Send>) {
+        self.outputs.insert(id, port);
+    }
+
+    /// Registers the supervisor of a supervised block.
+    #[cfg(feature = "std")]
+    pub(crate) fn supervise(&mut self, block: &BlockHandle, supervisor: SupervisorHandle) {
+        self.supervisors.push((block.clone(), supervisor));
+    }
+
+    /// Returns the supervisor of the given block, if it was registered.
+    pub(crate) fn supervisor<T: Block + 'static>(
+        &self,
+        block: &Arc<T>,
+    ) -> Option<SupervisorHandle> {
+        self.supervisors
+            .iter()
+            .find(|(handle, _)| handle.is(block))
+            .map(|(_, supervisor)| supervisor.clone())
+    }
+
+    /// Takes the sending end of an exported input port, unless it wasn't
+    /// exported, was already taken, or has a message type other than `T`.
+    pub(crate) fn take_input<T: Send + 'static>(
+        &mut self,
+        id: InputPortId,
+    ) -> Option<Box<dyn OutputPort<T> + Send>> {
+        take(&mut self.inputs, id)
+    }
+
+    /// Takes the receiving end of an exported output port, unless it wasn't
+    /// exported, was already taken, or has a message type other than `T`.
+    pub(crate) fn take_output<T: Send + 'static>(
+        &mut self,
+        id: OutputPortId,
+    ) -> Option<Box<dyn InputPort<T> + Send>> {
+        take(&mut self.outputs, id)
+    }
+
+    /// Drops the exported input ports that weren't taken, as they can't be
+    /// fed anymore once the system executes.
+    pub(crate) fn close_inputs(&mut self) {
+        self.inputs.clear();
+    }
+}
+
+fn take<K: Ord, P: 'static>(ports: &mut BTreeMap<K, Box<dyn Any + Send>>, id: K) -> Option<P> {
+    let port = ports.remove(&id)?;
+    match port.downcast() {
+        Ok(port) => Some(*port),
+        Err(port) => {
+            ports.insert(id, port);
+            None
+        },
+    }
+}
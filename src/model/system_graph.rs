@@ -0,0 +1,405 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    Block, BlockHandle, FanOutPolicy, InitialPacket, InputPortId, OutputPortId, PortId, PortType,
+    SystemBuildError, SystemBuilder, SystemDefinition,
+};
+use crate::io::{FanOutKind, RestartPolicy};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GraphError {
+    #[error("unknown block type: {0}")]
+    UnknownBlockType(String),
+
+    #[error("unknown port: {0}")]
+    UnknownPort(String),
+
+    #[error("untyped port: {0}")]
+    UntypedPort(String),
+
+    #[error("unregistered port ID: {0}")]
+    UnregisteredPort(PortId),
+
+    #[error("invalid initial packet for port: {0}")]
+    InvalidPacket(String),
+
+    #[error("unserializable initial packet for port: {0}")]
+    UnserializablePacket(String),
+
+    #[error("unsupported {1} fan-out for port: {0}")]
+    UnsupportedFanOut(String, FanOutKind),
+
+    #[error("unsupported by the format: {0}")]
+    Unsupported(&'static str),
+
+    #[error(transparent)]
+    Build(#[from] SystemBuildError),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A declarative, serializable system graph.
+///
+/// Blocks are instantiated by type name through a [`GraphLoader`], and
+/// named by their key in the `blocks` object. Ports are referenced as
+/// `block.port`, where `port` is either the name of the port, matched
+/// ignoring ASCII case, or its index among the block's input ports or
+/// output ports, depending on the context.
+///
+/// # Examples
+///
+/// ```json
+/// {
+///   "blocks": {
+///     "source": { "type": "Numbers" },
+///     "sqrt": {
+///       "type": "Sqrt",
+///       "restart": { "on-failure": { "max_retries": 3, "backoff": { "secs": 1, "nanos": 0 } } }
+///     }
+///   },
+///   "connections": [{ "from": "source.0", "to": "sqrt.0" }],
+///   "inputs": [],
+///   "outputs": ["sqrt.0"],
+///   "export_names": { "sqrt.0": "OUT" },
+///   "fan_outs": { "source.0": "round-robin" },
+///   "dead_letters": [],
+///   "packets": [{ "to": "source.0", "data": 42 }]
+/// }
+/// ```
+///
+/// All fields but `blocks` are optional.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SystemGraph {
+    /// The block instances, by name.
+    pub blocks: BTreeMap<String, GraphBlock>,
+
+    /// The connections from output ports to input ports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<GraphConnection>,
+
+    /// The exported input ports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+
+    /// The exported output ports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+
+    /// The names of exported ports outside the system, such as in the FBP
+    /// DSL, by port.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub export_names: BTreeMap<String, String>,
+
+    /// The fan-out policies of output ports.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fan_outs: BTreeMap<String, FanOutKind>,
+
+    /// The connections from output ports to dead-letter input ports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dead_letters: Vec<GraphConnection>,
+
+    /// The initial packets sent to input ports, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packets: Vec<GraphPacket>,
+}
+
+/// A block instance in a [`SystemGraph`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GraphBlock {
+    /// The block type name.
+    #[serde(rename = "type")]
+    pub r#type: String,
+
+    /// How the block is restarted once it completes.
+    #[serde(default, skip_serializing_if = "is_never")]
+    pub restart: RestartPolicy,
+}
+
+/// A connection in a [`SystemGraph`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GraphConnection {
+    pub from: String,
+    pub to: String,
+}
+
+/// An initial packet in a [`SystemGraph`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GraphPacket {
+    pub to: String,
+    pub data: Value,
+}
+
+fn is_never(policy: &RestartPolicy) -> bool {
+    *policy == RestartPolicy::Never
+}
+
+/// Instantiates the blocks, initial packets, and fan-out policies of a
+/// [`SystemGraph`].
+pub trait GraphLoader {
+    /// Instantiates a block of the given type name.
+    fn block(&mut self, r#type: &str) -> Option<Arc<dyn Block>>;
+
+    /// Decodes an initial packet for an input port of the given type.
+    fn packet(&mut self, port_type: PortType, data: &Value) -> Option<InitialPacket> {
+        let _ = (port_type, data);
+        None
+    }
+
+    /// Instantiates a fan-out policy for an output port of the given type.
+    ///
+    /// Only round-robin fan-outs are supported by default, since the other
+    /// kinds depend on the message type.
+    fn fan_out(&mut self, port_type: PortType, kind: FanOutKind) -> Option<FanOutPolicy> {
+        match kind {
+            FanOutKind::RoundRobin => Some((port_type.round_robin)()),
+            _ => None,
+        }
+    }
+}
+
+impl SystemGraph {
+    /// Parses a system graph from JSON.
+    pub fn from_json(json: &str) -> Result<Self, GraphError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Formats this system graph as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, GraphError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Builds a system definition from this graph, instantiating its blocks
+    /// with the given loader.
+    ///
+    /// Returns an error if a port without a [`PortDefinition`] is connected,
+    /// since its message type is unknown.
+    ///
+    /// [`PortDefinition`]: super::PortDefinition
+    pub fn build(&self, loader: &mut impl GraphLoader) -> Result<SystemDefinition, GraphError> {
+        let mut builder = GraphBuilder::new(loader);
+        for (name, block) in &self.blocks {
+            builder.block(name, block)?;
+        }
+        for (output, &kind) in &self.fan_outs {
+            builder.fan_out(output, kind)?;
+        }
+        for GraphConnection { from, to } in &self.connections {
+            builder.connect(from, to)?;
+        }
+        for GraphConnection { from, to } in &self.dead_letters {
+            builder.connect_dead_letter(from, to)?;
+        }
+        for input in &self.inputs {
+            builder.export_input(input)?;
+        }
+        for output in &self.outputs {
+            builder.export_output(output)?;
+        }
+        for GraphPacket { to, data } in &self.packets {
+            builder.packet(to, data)?;
+        }
+        builder.build().map_err(|(error, _)| error.into())
+    }
+}
+
+/// Builds a system definition step by step from the parts of a graph.
+pub(crate) struct GraphBuilder<'a, L: GraphLoader> {
+    loader: &'a mut L,
+    builder: SystemBuilder,
+    ports: PortResolver,
+}
+
+impl<'a, L: GraphLoader> GraphBuilder<'a, L> {
+    pub(crate) fn new(loader: &'a mut L) -> Self {
+        Self {
+            loader,
+            builder: SystemBuilder::new(),
+            ports: PortResolver::default(),
+        }
+    }
+
+    pub(crate) fn block(&mut self, name: &str, block: &GraphBlock) -> Result<(), GraphError> {
+        let handle = self
+            .loader
+            .block(&block.r#type)
+            .ok_or_else(|| GraphError::UnknownBlockType(block.r#type.clone()))?;
+        let handle = BlockHandle::new(handle)
+            .with_name(name)
+            .with_type_name(&block.r#type)
+            .with_restart_policy(block.restart);
+        self.ports.add(name, &handle);
+        self.builder.register_handle(handle);
+        Ok(())
+    }
+
+    pub(crate) fn fan_out(&mut self, output: &str, kind: FanOutKind) -> Result<(), GraphError> {
+        let (id, port_type) = self.ports.output(output)?;
+        let policy = self
+            .loader
+            .fan_out(port_type, kind)
+            .ok_or_else(|| GraphError::UnsupportedFanOut(output.into(), kind))?;
+        Ok(self.builder.fan_out_port(id, policy)?)
+    }
+
+    pub(crate) fn connect(&mut self, from: &str, to: &str) -> Result<(), GraphError> {
+        let (output, port_type) = self.ports.output(from)?;
+        let (input, _) = self.ports.input(to)?;
+        self.builder.connect_ports(output, input, port_type)?;
+        Ok(())
+    }
+
+    pub(crate) fn connect_dead_letter(&mut self, from: &str, to: &str) -> Result<(), GraphError> {
+        let (output, _) = self.ports.output(from)?;
+        let (input, port_type) = self.ports.input(to)?;
+        Ok(self
+            .builder
+            .connect_dead_letter_port(output, input, port_type)?)
+    }
+
+    pub(crate) fn export_input(&mut self, input: &str) -> Result<(), GraphError> {
+        self.builder.export_input(self.ports.input(input)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn export_output(&mut self, output: &str) -> Result<(), GraphError> {
+        self.builder.export_output(self.ports.output(output)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn packet(&mut self, to: &str, data: &Value) -> Result<(), GraphError> {
+        let (input, port_type) = self.ports.input(to)?;
+        let packet = self
+            .loader
+            .packet(port_type, data)
+            .ok_or_else(|| GraphError::InvalidPacket(to.into()))?;
+        Ok(self.builder.initial_packet_port(input, packet)?)
+    }
+
+    /// Builds the system definition, returning any error along with the
+    /// name of the block owning the port that it concerns.
+    pub(crate) fn build(self) -> Result<SystemDefinition, (SystemBuildError, Option<String>)> {
+        let owners = self.ports.owners;
+        self.builder.build().map_err(|error| {
+            let owner = error.port().and_then(|id| owners.get(&id).cloned());
+            (error, owner)
+        })
+    }
+}
+
+impl SystemDefinition {
+    /// Describes this system definition as a graph.
+    ///
+    /// Unnamed blocks are named after their type. Returns an error if an
+    /// initial packet wasn't decoded from JSON, since it can't be encoded.
+    pub fn to_graph(&self) -> Result<SystemGraph, GraphError> {
+        let mut graph = SystemGraph::default();
+        let mut refs = BTreeMap::<PortId, String>::new();
+        for (block, name) in self.blocks.iter().zip(self.block_names()) {
+            let r#type = block.type_name().into_owned();
+            for (id, port) in block.port_names() {
+                refs.insert(id, format!("{name}.{port}"));
+            }
+            let restart = block.restart_policy();
+            graph.blocks.insert(name, GraphBlock { r#type, restart });
+        }
+
+        let port = |id: PortId| {
+            refs.get(&id)
+                .cloned()
+                .ok_or(GraphError::UnregisteredPort(id))
+        };
+        for &(from, to) in self.connections.keys() {
+            let (from, to) = (port(from.into())?, port(to.into())?);
+            graph.connections.push(GraphConnection { from, to });
+        }
+        for (&from, &(to, _)) in &self.dead_letters {
+            let (from, to) = (port(from.into())?, port(to.into())?);
+            graph.dead_letters.push(GraphConnection { from, to });
+        }
+        for (&id, _) in self.inputs.iter() {
+            graph.inputs.push(port(id.into())?);
+        }
+        for (&id, _) in self.outputs.iter() {
+            graph.outputs.push(port(id.into())?);
+        }
+        for (&id, policy) in &self.fan_outs {
+            graph.fan_outs.insert(port(id.into())?, policy.kind());
+        }
+        for (id, packet) in &self.initial_packets {
+            let to = port((*id).into())?;
+            let Some(data) = packet.json() else {
+                return Err(GraphError::UnserializablePacket(to));
+            };
+            let data = data.clone();
+            graph.packets.push(GraphPacket { to, data });
+        }
+        Ok(graph)
+    }
+}
+
+/// Resolves `block.port` references to port IDs and types.
+#[derive(Default)]
+struct PortResolver {
+    blocks: BTreeMap<String, (Vec<InputPortId>, Vec<OutputPortId>)>,
+    types: BTreeMap<PortId, PortType>,
+    names: BTreeMap<PortId, &'static str>,
+    owners: BTreeMap<PortId, String>,
+}
+
+impl PortResolver {
+    fn add(&mut self, name: &str, block: &BlockHandle) {
+        let ports = (block.inputs(), block.outputs());
+        let ids = ports.0.iter().map(|&id| PortId::from(id));
+        let ids = ids.chain(ports.1.iter().map(|&id| id.into()));
+        self.owners.extend(ids.map(|id| (id, name.to_string())));
+        self.blocks.insert(name.to_string(), ports);
+        for port in block.ports() {
+            self.types.insert(port.id, port.r#type);
+            if let Some(name) = port.name {
+                self.names.insert(port.id, name);
+            }
+        }
+    }
+
+    fn input(&self, port: &str) -> Result<(InputPortId, PortType), GraphError> {
+        self.resolve(port, |(inputs, _)| inputs)
+    }
+
+    fn output(&self, port: &str) -> Result<(OutputPortId, PortType), GraphError> {
+        self.resolve(port, |(_, outputs)| outputs)
+    }
+
+    fn resolve<K: Copy + Into<PortId>>(
+        &self,
+        port: &str,
+        ports: impl Fn(&(Vec<InputPortId>, Vec<OutputPortId>)) -> &Vec<K>,
+    ) -> Result<(K, PortType), GraphError> {
+        let id = port
+            .rsplit_once('.')
+            .and_then(|(block, port)| {
+                let ports = ports(self.blocks.get(block)?);
+                match port.parse::<usize>() {
+                    Ok(index) => ports.get(index).copied(),
+                    Err(_) => ports.iter().copied().find(|&id| {
+                        let name = self.names.get(&id.into());
+                        name.is_some_and(|name| name.eq_ignore_ascii_case(port))
+                    }),
+                }
+            })
+            .ok_or_else(|| GraphError::UnknownPort(port.to_string()))?;
+        let port_type = self.types.get(&id.into()).copied();
+        let port_type = port_type.ok_or_else(|| GraphError::UntypedPort(port.to_string()))?;
+        Ok((id, port_type))
+    }
+}
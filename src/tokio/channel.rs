@@ -2,7 +2,7 @@
 
 use super::{Inputs, Outputs};
 use crate::{Connection, PortEvent};
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 use core::any::TypeId;
 use tokio::sync::mpsc;
 
@@ -15,14 +15,18 @@ pub struct Channel<T, const N: isize = UNLIMITED> {
     pub rx: Inputs<T, N>,
 }
 
-impl<T, const N: isize> Channel<T, N> {
+impl<T> Channel<T> {
     pub fn pair() -> (Channel<T, UNLIMITED>, Channel<T, UNLIMITED>) {
         (Self::bounded(1), Self::bounded(1))
     }
 
     /// Creates a one-shot connection.
+    ///
+    /// The output port disconnects after sending its first message, and the
+    /// input port after receiving it.
     pub fn oneshot() -> Channel<T, ONESHOT> {
-        Channel::from(mpsc::channel(1))
+        // Leave room for the disconnect event following the message:
+        Channel::from(mpsc::channel(2))
     }
 
     /// Creates a bounded connection.
@@ -30,19 +34,30 @@ impl<T, const N: isize> Channel<T, N> {
         Channel::from(mpsc::channel(buffer))
     }
 
-    /// Creates a bounded, type-erased connection.
-    #[allow(unused)]
+    /// Creates a bounded, type-erased connection merging the given number
+    /// of senders into one receiver.
+    #[allow(clippy::type_complexity)]
     pub(crate) fn bounded_boxed(
         buffer: usize,
+        senders: usize,
     ) -> (
-        Box<dyn crate::io::OutputPort<T> + Send>,
+        Vec<Box<dyn crate::io::OutputPort<T> + Send>>,
         Box<dyn crate::io::InputPort<T> + Send>,
     )
     where
-        T: Send + Sync + 'static,
+        T: Send + 'static,
     {
-        let (outputs, inputs) = Self::bounded(buffer).into_inner();
-        (Box::new(outputs), Box::new(inputs))
+        let (outputs, mut inputs) = Self::bounded(buffer).into_inner();
+        inputs.merge(senders.saturating_sub(1));
+        // Each sender is a distinct upstream connection, not a clone:
+        let others: Vec<_> = (1..senders)
+            .map(|_| Outputs::from(outputs.as_ref()))
+            .collect();
+        let outputs = core::iter::once(outputs)
+            .chain(others)
+            .map(|outputs| Box::new(outputs) as _)
+            .collect();
+        (outputs, Box::new(inputs))
     }
 }
 
@@ -15,6 +15,9 @@ pub use output::*;
 mod outputs;
 pub use outputs::*;
 
+mod shutdown;
+pub use shutdown::*;
+
 #[cfg(feature = "std")]
 mod stderr;
 #[cfg(feature = "std")]
@@ -37,3 +40,6 @@ pub use scheduler::*;
 
 mod system;
 pub use system::*;
+
+mod task;
+pub(crate) use task::TaskSet;
@@ -1,5 +1,10 @@
 // This is free and unencumbered software released into the public domain.
 
+#[cfg(feature = "serial")]
+mod deterministic;
+#[cfg(feature = "serial")]
+pub use deterministic::*;
+
 #[cfg(feature = "parallel")]
 mod parallel;
 #[cfg(feature = "parallel")]
@@ -0,0 +1,314 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(feature = "executor")]
+use crate::{Error, Result};
+use crate::{Scheduler, Spawner, Task, Timer};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    task::Wake,
+    vec::Vec,
+};
+use core::{
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use std::sync::Mutex;
+
+/// A single-threaded scheduler that polls its tasks in a fixed order
+/// determined by a seed, with virtual time.
+///
+/// Given the same tasks and seed, every run produces the same interleaving,
+/// which makes for reproducible tests. Time only advances, instantly, once
+/// all tasks are waiting on the scheduler's [`VirtualClock`].
+///
+/// Tasks must not depend on another runtime's I/O or timers.
+pub struct DeterministicScheduler {
+    tasks: BTreeMap<usize, Task>,
+    next_id: usize,
+    rng: SplitMix64,
+    spawned: Arc<Mutex<Vec<Task>>>,
+    ready: Arc<Mutex<BTreeSet<usize>>>,
+    clock: VirtualClock,
+}
+
+impl core::fmt::Debug for DeterministicScheduler {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeterministicScheduler")
+            .field("tasks", &self.tasks.len())
+            .field("clock", &self.clock.now())
+            .finish()
+    }
+}
+
+impl DeterministicScheduler {
+    /// Instantiates a new scheduler, polling its tasks in the order
+    /// determined by the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            tasks: BTreeMap::new(),
+            next_id: 0,
+            rng: SplitMix64(seed),
+            spawned: Arc::default(),
+            ready: Arc::default(),
+            clock: VirtualClock::default(),
+        }
+    }
+
+    /// Returns the virtual clock of this scheduler.
+    pub fn clock(&self) -> VirtualClock {
+        self.clock.clone()
+    }
+
+    /// Returns a handle for spawning tasks on this scheduler, such as the
+    /// blocks of an [`executor::System`](crate::executor::System), whose
+    /// port timeouts, restart backoffs, and shutdown grace periods then
+    /// wait on the virtual clock.
+    pub fn spawner(&self) -> DeterministicSpawner {
+        DeterministicSpawner {
+            spawned: Arc::clone(&self.spawned),
+            clock: self.clock.clone(),
+        }
+    }
+
+    /// Executes the given system on this scheduler, blocking until
+    /// completion.
+    ///
+    /// The system should have been built with the [`spawner`](Self::spawner)
+    /// of this scheduler. Fails if the system stalls, with all of its blocks
+    /// waiting on something other than the virtual clock.
+    #[cfg(feature = "executor")]
+    pub fn run_system(&mut self, system: crate::executor::System) -> Result {
+        let result = Arc::new(Mutex::new(None));
+        let output = Arc::clone(&result);
+        Spawner::spawn(
+            self,
+            Box::pin(async move {
+                *output.lock().unwrap() = Some(system.execute().await);
+            }),
+        );
+        self.run();
+        let result = result.lock().unwrap().take();
+        result.unwrap_or_else(|| {
+            self.shutdown();
+            Err(Error::Other("system stalled".into()))
+        })
+    }
+
+    /// Moves newly spawned tasks into the set of tasks, ready to be polled.
+    fn admit(&mut self) {
+        let spawned = core::mem::take(&mut *self.spawned.lock().unwrap());
+        let mut ready = self.ready.lock().unwrap();
+        for task in spawned {
+            self.tasks.insert(self.next_id, task);
+            ready.insert(self.next_id);
+            self.next_id += 1;
+        }
+    }
+
+    /// Polls one of the ready tasks, chosen pseudo-randomly, returning
+    /// whether there was any.
+    fn step(&mut self) -> bool {
+        self.admit();
+        let id = {
+            let mut ready = self.ready.lock().unwrap();
+            if ready.is_empty() {
+                return false;
+            }
+            let index = (self.rng.next() % ready.len() as u64) as usize;
+            let id = *ready.iter().nth(index).unwrap();
+            ready.remove(&id);
+            id
+        };
+        let Some(task) = self.tasks.get_mut(&id) else {
+            return true; // already completed
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: Arc::clone(&self.ready),
+        }));
+        if task
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_ready()
+        {
+            self.tasks.remove(&id);
+        }
+        true
+    }
+}
+
+impl Scheduler for DeterministicScheduler {
+    /// Runs all spawned tasks to completion, or until they are all waiting
+    /// on something other than the virtual clock.
+    fn run(&mut self) {
+        loop {
+            if self.step() {
+                continue;
+            }
+            if !self.clock.advance() {
+                break;
+            }
+        }
+    }
+
+    fn shutdown(&mut self) {
+        self.spawned.lock().unwrap().clear();
+        self.ready.lock().unwrap().clear();
+        self.tasks.clear();
+    }
+}
+
+impl Spawner for DeterministicScheduler {
+    fn spawn(&self, task: Task) {
+        self.spawned.lock().unwrap().push(task);
+    }
+
+    fn timer(&self) -> Option<Arc<dyn Timer + Send + Sync>> {
+        Some(Arc::new(self.clock.clone()))
+    }
+}
+
+/// A handle for spawning tasks on a [`DeterministicScheduler`].
+///
+/// See [`DeterministicScheduler::spawner`].
+#[derive(Clone)]
+pub struct DeterministicSpawner {
+    spawned: Arc<Mutex<Vec<Task>>>,
+    clock: VirtualClock,
+}
+
+impl core::fmt::Debug for DeterministicSpawner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeterministicSpawner")
+            .field("clock", &self.clock.now())
+            .finish()
+    }
+}
+
+impl Spawner for DeterministicSpawner {
+    fn spawn(&self, task: Task) {
+        self.spawned.lock().unwrap().push(task);
+    }
+
+    fn timer(&self) -> Option<Arc<dyn Timer + Send + Sync>> {
+        Some(Arc::new(self.clock.clone()))
+    }
+}
+
+struct TaskWaker {
+    id: usize,
+    ready: Arc<Mutex<BTreeSet<usize>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().insert(self.id);
+    }
+}
+
+/// The virtual time of a [`DeterministicScheduler`].
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock(Arc<Mutex<ClockState>>);
+
+#[derive(Debug, Default)]
+struct ClockState {
+    now: Duration,
+    /// The wakers of sleeping tasks, by deadline and registration order.
+    timers: BTreeMap<(Duration, usize), Waker>,
+    next_timer: usize,
+}
+
+impl VirtualClock {
+    /// Returns the virtual time elapsed since the scheduler was created.
+    pub fn now(&self) -> Duration {
+        self.0.lock().unwrap().now
+    }
+
+    /// Waits until the given virtual duration has elapsed.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep {
+            clock: self.clone(),
+            deadline: self.now() + duration,
+            timer: None,
+        }
+    }
+
+    /// Advances the virtual time to the earliest deadline of any sleeping
+    /// task, waking up all tasks due by then, and returns whether there was
+    /// any.
+    fn advance(&self) -> bool {
+        let mut state = self.0.lock().unwrap();
+        let Some((&(deadline, _), _)) = state.timers.first_key_value() else {
+            return false;
+        };
+        state.now = state.now.max(deadline);
+        let later = state.timers.split_off(&(deadline, usize::MAX));
+        let due = core::mem::replace(&mut state.timers, later);
+        drop(state);
+        due.into_values().for_each(Waker::wake);
+        true
+    }
+}
+
+impl Timer for VirtualClock {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(VirtualClock::sleep(self, duration))
+    }
+}
+
+/// A future that completes once a virtual duration has elapsed.
+///
+/// See [`VirtualClock::sleep`].
+#[derive(Debug)]
+pub struct Sleep {
+    clock: VirtualClock,
+    deadline: Duration,
+    /// The registration number of the pending timer, once polled.
+    timer: Option<usize>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let clock = self.clock.clone();
+        let mut state = clock.0.lock().unwrap();
+        if state.now >= self.deadline {
+            return Poll::Ready(());
+        }
+        let timer = *self.timer.get_or_insert_with(|| {
+            state.next_timer += 1;
+            state.next_timer
+        });
+        state
+            .timers
+            .insert((self.deadline, timer), cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer {
+            let mut state = self.clock.0.lock().unwrap();
+            state.timers.remove(&(self.deadline, timer));
+        }
+    }
+}
+
+/// The SplitMix64 pseudo-random number generator.
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -1,19 +1,53 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::Scheduler;
-use tokio::runtime::{Builder, Runtime};
+use crate::{Result, Scheduler, Spawner, Task, tokio::System};
+use std::sync::Mutex;
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinSet,
+};
 
 #[derive(Debug)]
 pub struct ParallelScheduler {
+    tasks: Mutex<JoinSet<()>>,
     runtime: Runtime,
 }
 
-impl Scheduler for ParallelScheduler {}
+impl Scheduler for ParallelScheduler {
+    fn run(&mut self) {
+        let mut tasks = core::mem::take(self.tasks.get_mut().unwrap());
+        self.runtime
+            .block_on(async move { while tasks.join_next().await.is_some() {} });
+    }
+
+    fn shutdown(&mut self) {
+        let _runtime = self.runtime.enter();
+        self.tasks.get_mut().unwrap().abort_all();
+    }
+}
+
+impl Spawner for ParallelScheduler {
+    fn spawn(&self, task: Task) {
+        self.tasks
+            .lock()
+            .unwrap()
+            .spawn_on(task, self.runtime.handle());
+    }
+}
 
 impl ParallelScheduler {
     pub fn new() -> std::io::Result<Self> {
         let runtime = Builder::new_multi_thread().enable_all().build()?;
-        Ok(Self { runtime })
+        let tasks = Mutex::new(JoinSet::new());
+        Ok(Self { tasks, runtime })
+    }
+
+    /// Executes the given system on this scheduler, blocking until
+    /// completion.
+    ///
+    /// See [`System::execute_on`].
+    pub fn run_system(&self, system: System) -> Result {
+        system.execute_on(self)
     }
 
     #[cfg(feature = "tokio")]
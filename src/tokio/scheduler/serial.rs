@@ -1,6 +1,9 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::{Channel, Inputs, Outputs, Scheduler};
+use crate::{
+    Result, Scheduler, Spawner, Task,
+    tokio::{Channel, Inputs, Outputs, System},
+};
 use tokio::{
     runtime::{Builder, Runtime},
     task::LocalSet,
@@ -12,7 +15,24 @@ pub struct SerialScheduler {
     runtime: Runtime,
 }
 
-impl Scheduler for SerialScheduler {}
+impl Scheduler for SerialScheduler {
+    fn run(&mut self) {
+        if let Some(tasks) = self.tasks.replace(LocalSet::new()) {
+            self.runtime.block_on(tasks);
+        }
+    }
+
+    fn shutdown(&mut self) {
+        let _runtime = self.runtime.enter();
+        self.tasks = Some(LocalSet::new()); // drops the pending tasks
+    }
+}
+
+impl Spawner for SerialScheduler {
+    fn spawn(&self, task: Task) {
+        SerialScheduler::spawn(self, task)
+    }
+}
 
 impl SerialScheduler {
     pub fn new() -> std::io::Result<Self> {
@@ -21,6 +41,14 @@ impl SerialScheduler {
         Ok(Self { tasks, runtime })
     }
 
+    /// Executes the given system on this scheduler, blocking until
+    /// completion.
+    ///
+    /// See [`System::execute_on`].
+    pub fn run_system(&self, system: System) -> Result {
+        system.execute_on(self)
+    }
+
     #[cfg(feature = "tokio")]
     pub fn id(&self) -> Option<tokio::runtime::Id> {
         self.tasks.as_ref().map(|tasks| tasks.id())
@@ -31,7 +59,7 @@ impl SerialScheduler {
         F: Future + 'static,
         F::Output: 'static,
     {
-        let _ = self.tasks.as_ref().unwrap().spawn_local(process);
+        self.tasks.as_ref().unwrap().spawn_local(process);
     }
 
     pub fn create<T, F>(&self, block: F) -> (Outputs<T>, Inputs<T>)
@@ -41,20 +69,12 @@ impl SerialScheduler {
         T: 'static,
     {
         let (input, output) = Channel::<T>::pair();
-        let _ = self
-            .tasks
+        self.tasks
             .as_ref()
             .unwrap()
             .spawn_local(async move { block(input.rx, output.tx).await });
         (input.tx, output.rx)
     }
-
-    pub async fn run(&mut self) {
-        match self.tasks.take() {
-            None => (),
-            Some(tasks) => tasks.await,
-        }
-    }
 }
 
 #[cfg(feature = "tokio")]
@@ -74,6 +94,6 @@ impl AsRef<tokio::runtime::Handle> for SerialScheduler {
 #[cfg(feature = "tokio")]
 impl AsRef<tokio::task::LocalSet> for SerialScheduler {
     fn as_ref(&self) -> &tokio::task::LocalSet {
-        &self.tasks.as_ref().unwrap()
+        self.tasks.as_ref().unwrap()
     }
 }
@@ -0,0 +1,88 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    error::Result,
+    io::{Either, InputPort, OutputPort, select},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::{pin::pin, time::Duration};
+use tokio::sync::watch;
+
+/// A handle for shutting down a running system.
+///
+/// Shutting down closes the system's exported input ports, lets its blocks
+/// drain their in-flight messages within a grace period, and then aborts
+/// any stragglers.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle(Arc<watch::Sender<Option<Duration>>>);
+
+impl Default for ShutdownHandle {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(None).0))
+    }
+}
+
+impl ShutdownHandle {
+    /// Requests the shutdown of the system, allowing its blocks the given
+    /// grace period to complete before they are aborted.
+    ///
+    /// Only the first request has any effect.
+    pub fn shutdown(&self, grace_period: Duration) {
+        self.0.send_if_modified(|state| {
+            let requested = state.is_none();
+            if requested {
+                *state = Some(grace_period);
+            }
+            requested
+        });
+    }
+
+    /// Checks whether the shutdown of the system has been requested.
+    pub fn is_shutdown(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    /// Waits until the shutdown of the system is requested, returning the
+    /// grace period.
+    pub(crate) fn requested(&self) -> impl Future<Output = Duration> + Send + 'static {
+        let mut state = self.0.subscribe();
+        async move {
+            let grace_period = match state.wait_for(Option::is_some).await {
+                Ok(grace_period) => *grace_period,
+                Err(_) => None, // the system is gone
+            };
+            match grace_period {
+                Some(grace_period) => grace_period,
+                None => core::future::pending().await,
+            }
+        }
+    }
+}
+
+/// Forwards messages from the given input port to the given output port
+/// until either port is disconnected, the given `closed` port signals that
+/// the receiving end was closed, or the shutdown is requested.
+pub(crate) async fn forward_until_shutdown<T: Send + 'static>(
+    mut input: Box<dyn InputPort<T> + Send>,
+    output: Arc<dyn OutputPort<T> + Send>,
+    mut closed: Box<dyn InputPort<()> + Send>,
+    shutdown: ShutdownHandle,
+) -> Result {
+    let mut requested = pin!(shutdown.requested());
+    loop {
+        let stopped = select(closed.recv(), &mut requested);
+        let message = match select(input.recv(), stopped).await {
+            Either::Left(message) => message?,
+            Either::Right(_) => None,
+        };
+        // Stop once the shutdown is requested or either side disconnects:
+        let sent = match message {
+            Some(message) => output.send(message).await.is_ok(),
+            None => false,
+        };
+        if !sent {
+            input.close();
+            return Ok(());
+        }
+    }
+}
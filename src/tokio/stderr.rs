@@ -1,10 +1,24 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::Inputs;
-use crate::error::Result;
-use alloc::string::ToString;
+use crate::{
+    error::Result,
+    io::InputPort,
+    model::{self, Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, PortDefinition},
+};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
-pub async fn stderr<T: ToString>(mut inputs: Inputs<T>) -> Result {
+/// Writes each message received as a line to the standard error.
+pub async fn stderr<T, I>(inputs: &mut I) -> Result
+where
+    T: ToString + Send + 'static,
+    I: InputPort<T> + ?Sized,
+{
     use tokio::io::AsyncWriteExt;
 
     let mut output = tokio::io::stderr();
@@ -20,3 +34,39 @@ pub async fn stderr<T: ToString>(mut inputs: Inputs<T>) -> Result {
 
     Ok(())
 }
+
+/// A block that writes each of its input messages as a line to standard
+/// error.
+pub struct Stderr<T = String> {
+    pub input: model::Inputs<T>,
+}
+
+impl<T> Default for Stderr<T> {
+    fn default() -> Self {
+        Self {
+            input: model::Inputs::named("in"),
+        }
+    }
+}
+
+impl<T> BlockName for Stderr<T> {
+    fn name(&self) -> Cow<'_, str> {
+        "stderr".into()
+    }
+}
+
+impl<T: ToString + Send + 'static> BlockDefinition for Stderr<T> {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.input).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: ToString + Send + 'static> Block for Stderr<T> {
+    async fn execute(&self, mut inputs: BlockInputs, _outputs: BlockOutputs) -> Result {
+        let Some(mut input) = inputs.take(&self.input) else {
+            return Ok(());
+        };
+        stderr(&mut *input).await
+    }
+}
@@ -1,10 +1,19 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::Outputs;
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    io::OutputPort,
+    model::{self, Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, PortDefinition},
+};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec, vec::Vec};
 use core::str::FromStr;
 
-pub async fn stdin<T: FromStr>(outputs: Outputs<T>) -> Result {
+/// Parses lines read from the standard input and sends them downstream.
+pub async fn stdin<T, O>(outputs: &O) -> Result
+where
+    T: FromStr + Send + 'static,
+    O: OutputPort<T> + ?Sized,
+{
     use std::io::ErrorKind;
     use tokio::io::{AsyncBufReadExt, BufReader};
 
@@ -21,3 +30,39 @@ pub async fn stdin<T: FromStr>(outputs: Outputs<T>) -> Result {
 
     Ok(())
 }
+
+/// A block that reads lines from standard input, sending each of them
+/// parsed as a message.
+pub struct Stdin<T = String> {
+    pub output: model::Outputs<T>,
+}
+
+impl<T> Default for Stdin<T> {
+    fn default() -> Self {
+        Self {
+            output: model::Outputs::named("out"),
+        }
+    }
+}
+
+impl<T> BlockName for Stdin<T> {
+    fn name(&self) -> Cow<'_, str> {
+        "stdin".into()
+    }
+}
+
+impl<T: FromStr + Send + 'static> BlockDefinition for Stdin<T> {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.output).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: FromStr + Send + 'static> Block for Stdin<T> {
+    async fn execute(&self, _inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let Some(output) = outputs.take(&self.output) else {
+            return Ok(());
+        };
+        stdin(&*output).await
+    }
+}
@@ -1,124 +1,72 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::{Channel, Inputs, Outputs};
-use crate::{error::Result, io::Message, model::SystemDefinition};
-use alloc::vec::Vec;
-use tokio::task::{AbortHandle, JoinSet};
+use super::TaskSet;
+#[cfg(feature = "std")]
+use super::{Inputs, Outputs};
+use crate::{error::Result, model};
+use tokio::runtime::{Handle, Runtime};
 
 pub type Subsystem = System;
 
-#[derive(Debug, Default)]
-pub struct System {
-    pub(crate) inputs: Vec<Inputs<Message>>,
-    pub(crate) outputs: Vec<Outputs<Message>>,
-    pub(crate) blocks: JoinSet<Result>,
-}
+/// A system of blocks, spawned on a Tokio runtime.
+pub type System = model::System<TaskSet>;
 
 impl System {
-    // pub fn oneshot<T>() -> Channel<T, ONESHOT> {
-    //     Channel::oneshot()
-    // }
-
-    // pub fn bounded<T>(buffer: usize) -> Channel<T, UNLIMITED> {
-    //     Channel::bounded(buffer)
-    // }
-
-    /// Builds and executes a system, blocking until completion.
-    pub async fn run<F: FnOnce(&mut Self)>(f: F) -> Result {
-        Self::build(f).execute().await
-    }
-
-    /// Builds a new system.
-    pub fn build<F: FnOnce(&mut Self)>(f: F) -> Self {
-        let mut system = Self::new();
+    /// Builds a new system whose blocks are spawned on the runtime of the
+    /// given scheduler, such as a [`SerialScheduler`](super::SerialScheduler)
+    /// or a [`ParallelScheduler`](super::ParallelScheduler).
+    pub fn build_on<S: AsRef<Handle>, F: FnOnce(&mut Self)>(scheduler: &S, f: F) -> Self {
+        let mut system = Self::new_on(scheduler);
         f(&mut system);
         system
     }
 
-    /// Instantiates a new system.
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    pub fn connect<T>(&mut self, inputs: Inputs<T>, outputs: Outputs<T>)
-    where
-        T: Send + 'static,
-    {
-        self.blocks.spawn(async move {
-            let mut inputs = inputs;
-            let outputs = outputs;
-            while let Some(input) = inputs.recv().await? {
-                outputs.send(input).await?;
-            }
-            Ok(())
-        });
-    }
-
-    pub fn spawn<F>(&mut self, task: F) -> AbortHandle
-    where
-        F: Future<Output = Result>,
-        F: Send + 'static,
-    {
-        self.blocks.spawn(task)
+    /// Instantiates a new system whose blocks are spawned on the runtime of
+    /// the given scheduler.
+    pub fn new_on<S: AsRef<Handle>>(scheduler: &S) -> Self {
+        Self::with_tasks(TaskSet::new_on(scheduler.as_ref().clone()))
     }
 
-    pub async fn execute(self) -> Result {
-        self.blocks.join_all().await;
-        Ok(())
+    /// Executes this system on the runtime of the given scheduler, blocking
+    /// until completion.
+    ///
+    /// The system should have been built with [`System::build_on`] or
+    /// [`System::new_on`] for the same scheduler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if blocks of this system were spawned on the runtime of
+    /// another scheduler, or on another ambient runtime, as they wouldn't
+    /// be driven to completion.
+    pub fn execute_on<S: AsRef<Runtime>>(self, scheduler: &S) -> Result {
+        if let Some(runtime) = &self.blocks.runtime {
+            assert_eq!(
+                runtime.id(),
+                scheduler.as_ref().handle().id(),
+                "system executed on another runtime than it was built on"
+            );
+        }
+        scheduler.as_ref().block_on(self.execute())
     }
 
     #[cfg(feature = "std")]
-    pub fn read_stdin<T: core::str::FromStr>(&mut self) -> Inputs<T>
+    pub fn read_stdin<T>(&mut self) -> Inputs<T>
     where
-        T: Send + 'static,
+        T: core::str::FromStr + Send + 'static,
         <T as core::str::FromStr>::Err: Send,
     {
-        let (output, input) = super::Channel::<T>::bounded(1).into_inner(); // TODO
-        let block = super::stdin(output);
-        self.blocks.spawn(block);
+        let (output, input) = super::Channel::<T>::bounded(1).into_inner();
+        self.spawn(async move { super::stdin(&output).await });
         input
     }
 
     #[cfg(feature = "std")]
-    pub fn write_stdout<T: alloc::string::ToString>(&mut self) -> Outputs<T>
+    pub fn write_stdout<T>(&mut self) -> Outputs<T>
     where
-        T: Send + 'static,
+        T: alloc::string::ToString + Send + 'static,
     {
-        let (output, input) = super::Channel::<T>::bounded(1).into_inner(); // TODO
-        let block = super::stdout(input);
-        self.blocks.spawn(block);
+        let (output, mut input) = super::Channel::<T>::bounded(1).into_inner();
+        self.spawn(async move { super::stdout(&mut input).await });
         output
     }
 }
-
-impl From<&SystemDefinition> for System {
-    fn from(system_definition: &SystemDefinition) -> Self {
-        let mut system = Self::new();
-
-        let input_max = system_definition.inputs_max().unwrap();
-        let input_ids = system_definition.inputs_range().unwrap();
-        let input_count = input_ids.count();
-        system.inputs.resize_with(input_count, Inputs::default);
-
-        let output_min = system_definition.outputs_min().unwrap();
-        let output_ids = system_definition.outputs_range().unwrap();
-        let output_count = output_ids.count();
-        system.outputs.resize_with(output_count, Outputs::default);
-
-        for ((output_id, input_id), _) in &system_definition.connections {
-            // TODO: support multiple connections to the same input port
-            let channel = Channel::<Message>::bounded(1);
-            let output_index = output_id.index() - output_min.index();
-            let input_index = input_id.index() - input_max.index();
-            system.outputs[output_index] = channel.tx;
-            system.inputs[input_index] = channel.rx;
-        }
-
-        // TODO: schedule system.blocks
-        for _block in &system_definition.blocks {
-            //system.blocks.spawn(block);
-        }
-
-        system
-    }
-}
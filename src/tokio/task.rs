@@ -0,0 +1,132 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::ShutdownHandle;
+use crate::{
+    PortEvent,
+    error::Result,
+    io::{Either, FailurePolicy, Failures, SharedTimer, Timer, select},
+    model::{ErasedChannel, ErasedTask, PortType, Tasks},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::{
+    any::Any,
+    pin::{Pin, pin},
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    sync::mpsc::{Receiver, Sender},
+    task::{AbortHandle, JoinSet},
+};
+
+/// A set of blocks, spawned on a Tokio runtime.
+#[derive(Default)]
+pub struct TaskSet {
+    tasks: JoinSet<Result>,
+    /// The runtime the blocks are spawned on, if given or once known.
+    pub(crate) runtime: Option<Handle>,
+}
+
+impl core::fmt::Debug for TaskSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TaskSet")
+            .field("tasks", &self.tasks)
+            .field("runtime", &self.runtime.as_ref().map(Handle::id))
+            .finish()
+    }
+}
+
+impl TaskSet {
+    /// Instantiates a new set of blocks spawned on the given runtime.
+    pub(crate) fn new_on(runtime: Handle) -> Self {
+        Self {
+            tasks: JoinSet::new(),
+            runtime: Some(runtime),
+        }
+    }
+}
+
+impl Tasks for TaskSet {
+    type Receiver<T: Send + 'static> = Receiver<PortEvent<T>>;
+    type Sender<T: Send + 'static> = Sender<PortEvent<T>>;
+    type AbortHandle = AbortHandle;
+    type ShutdownHandle = ShutdownHandle;
+
+    fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = Result> + Send + 'static,
+    {
+        // Record the ambient runtime, unless given one, for `execute_on`:
+        let runtime = self.runtime.get_or_insert_with(Handle::current);
+        #[cfg(feature = "std")]
+        let task = crate::io::AsyncContext::new(task);
+        self.tasks.spawn_on(task, runtime)
+    }
+
+    fn timer(&self) -> SharedTimer {
+        Arc::new(TokioTimer)
+    }
+
+    async fn join_all(&mut self, policy: FailurePolicy, shutdown: &ShutdownHandle) -> Result {
+        let mut failures = Failures::new(policy);
+        let mut requested = pin!(shutdown.requested());
+        let mut deadline = None;
+        loop {
+            let result = match deadline {
+                None => match select(self.tasks.join_next(), &mut requested).await {
+                    Either::Left(result) => result,
+                    Either::Right(grace_period) => {
+                        deadline = Some(tokio::time::Instant::now() + grace_period);
+                        continue;
+                    },
+                },
+                Some(deadline) => {
+                    match tokio::time::timeout_at(deadline, self.tasks.join_next()).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            self.tasks.shutdown().await; // abort the stragglers
+                            None
+                        },
+                    }
+                },
+            };
+            let Some(result) = result else {
+                break;
+            };
+            let error = match result {
+                Ok(Ok(())) => continue,
+                Ok(Err(error)) => error,
+                Err(error) if error.is_cancelled() => continue,
+                Err(error) => error.into(),
+            };
+            if let Some(error) = failures.record(error) {
+                self.tasks.shutdown().await;
+                return Err(error);
+            }
+        }
+        failures.into_result()
+    }
+
+    fn channel(port_type: PortType, buffer: usize, senders: usize) -> ErasedChannel {
+        (port_type.tokio_channel)(buffer, senders)
+    }
+
+    fn forward(
+        port_type: PortType,
+        input: Box<dyn Any + Send>,
+        output: Box<dyn Any + Send>,
+        closed: Box<dyn Any + Send>,
+        shutdown: ShutdownHandle,
+    ) -> ErasedTask {
+        (port_type.tokio_forward)(input, output, closed, shutdown)
+    }
+}
+
+/// The timer of the Tokio runtime.
+struct TokioTimer;
+
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
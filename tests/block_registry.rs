@@ -0,0 +1,145 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "std", feature = "tokio"))]
+
+mod common;
+
+use common::Forward;
+
+use async_flow::model::{BlockRegistry, PortType, SystemBuildError, SystemBuilder};
+
+#[test]
+fn builtins() {
+    let registry = BlockRegistry::with_builtins();
+    let names = registry.types().map(|t| t.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["stderr", "stdin", "stdout"]);
+
+    let stdin = registry.get("stdin").unwrap();
+    assert!(stdin.inputs().is_empty());
+    assert_eq!(stdin.outputs()[0].r#type, Some(PortType::of::<String>()));
+    let stdout = registry.get("stdout").unwrap();
+    assert_eq!(stdout.inputs()[0].r#type, Some(PortType::of::<String>()));
+    assert!(stdout.outputs().is_empty());
+}
+
+#[test]
+fn duplicate_block_type() {
+    use async_flow::{model::BlockType, tokio::Stdin};
+
+    let mut registry = BlockRegistry::with_builtins();
+    assert!(!registry.register(Stdin::<i64>::default));
+    let stdin = registry.get("stdin").unwrap();
+    assert_eq!(stdin.outputs()[0].r#type, Some(PortType::of::<String>()));
+
+    let numbers = BlockType::new(Stdin::<i64>::default).with_name("stdin-i64");
+    assert!(registry.register_type(numbers));
+    let stdin = registry.get("stdin-i64").unwrap();
+    assert_eq!(stdin.outputs()[0].r#type, Some(PortType::of::<i64>()));
+}
+
+#[test]
+fn unknown_block_type() {
+    let registry = BlockRegistry::new();
+    let mut builder = SystemBuilder::new();
+    assert!(matches!(
+        builder.instantiate(&registry, "Forward"),
+        Err(SystemBuildError::UnknownBlockType(_))
+    ));
+}
+
+#[test]
+fn mismatched_connection() {
+    let mut registry = BlockRegistry::with_builtins();
+    registry.register(Forward::default);
+    let mut builder = SystemBuilder::new();
+    let stdin = builder.instantiate(&registry, "stdin").unwrap();
+    let forward = builder.instantiate(&registry, "Forward").unwrap();
+    assert!(matches!(
+        builder.connect_by_id(stdin.outputs()[0], forward.inputs()[0]),
+        Err(SystemBuildError::MismatchedType(..))
+    ));
+}
+
+#[tokio::test]
+async fn instantiate_by_name() {
+    let mut registry = BlockRegistry::new();
+    registry.register(Forward::default);
+    let mut builder = SystemBuilder::new();
+    let first = builder.instantiate(&registry, "Forward").unwrap();
+    let second = builder.instantiate(&registry, "Forward").unwrap();
+    builder
+        .connect_by_id(first.outputs()[0], second.inputs()[0])
+        .unwrap();
+    let input = builder
+        .export_input((first.inputs()[0], PortType::of::<i64>()))
+        .unwrap();
+    let output = builder
+        .export_output((second.outputs()[0], PortType::of::<i64>()))
+        .unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input_port::<i64>(input).unwrap();
+    let mut outputs = system.take_output_port::<i64>(output).unwrap();
+    inputs.send(42).await.unwrap();
+    drop(inputs);
+    assert_eq!(outputs.recv().await, Ok(Some(42)));
+    assert_eq!(outputs.recv().await, Ok(None));
+    system.execute().await.unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn load_graph() {
+    use async_flow::model::SystemGraph;
+
+    let mut registry = BlockRegistry::new();
+    registry.register(Forward::default);
+    registry.register_message::<i64>();
+    let graph = SystemGraph::from_json(
+        r#"{
+            "blocks": {
+                "source": {"type": "Forward"},
+                "left": {"type": "Forward"},
+                "right": {"type": "Forward"}
+            },
+            "connections": [
+                {"from": "source.0", "to": "left.0"},
+                {"from": "source.0", "to": "right.0"}
+            ],
+            "outputs": ["left.0", "right.0"],
+            "fan_outs": {"source.0": "broadcast"},
+            "packets": [{"to": "source.0", "data": 7}]
+        }"#,
+    )
+    .unwrap();
+    let definition = graph.build(&mut registry).unwrap();
+
+    let mut system = definition.prepare();
+    for (&output, _) in definition.outputs.iter() {
+        let mut outputs = system.take_output_port::<i64>(output).unwrap();
+        assert_eq!(outputs.recv().await, Ok(Some(7)));
+        assert_eq!(outputs.recv().await, Ok(None));
+    }
+    system.execute().await.unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn registered_name_round_trip() {
+    use async_flow::model::{BlockType, SystemGraph};
+
+    let mut registry = BlockRegistry::new();
+    registry.register_type(BlockType::new(Forward::default).with_name("relay"));
+    let mut builder = SystemBuilder::new();
+    builder.instantiate(&registry, "relay").unwrap();
+    let graph = builder.build().unwrap().to_graph().unwrap();
+    assert_eq!(graph.blocks["relay0"].r#type, "relay");
+
+    let json = graph.to_json().unwrap();
+    let rebuilt = SystemGraph::from_json(&json)
+        .unwrap()
+        .build(&mut registry)
+        .unwrap();
+    assert_eq!(rebuilt.to_graph().unwrap(), graph);
+}
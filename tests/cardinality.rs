@@ -0,0 +1,169 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use async_flow::{
+    Result, SendError,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, Input, InputPortId, Output,
+        OutputPortId, Outputs, PortDefinition, SystemBuildError, SystemBuilder,
+    },
+};
+use std::borrow::Cow;
+
+/// A block that forwards the single number it receives.
+#[derive(Default)]
+struct Once {
+    input: Input<i64>,
+    output: Outputs<i64, -1, 1>,
+}
+
+impl BlockName for Once {
+    fn name(&self) -> Cow<'_, str> {
+        "Once".into()
+    }
+}
+
+impl BlockDefinition for Once {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.input).into(), (&self.output).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Once {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        let output = outputs.take(&self.output).unwrap();
+        for x in input.recv_all().await? {
+            output.send(x).await?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn underconnected_output() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(Once::default());
+    builder.export(&block.input).unwrap();
+    assert!(matches!(
+        builder.build(),
+        Err(SystemBuildError::UnderconnectedOutput(id, _, 0, 1)) if id == block.output.id()
+    ));
+}
+
+#[test]
+fn overconnected_input() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Once::default());
+    let second = builder.register(Once::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.input).unwrap();
+    builder.export(&second.output).unwrap();
+    assert!(matches!(
+        builder.build(),
+        Err(SystemBuildError::OverconnectedInput(id, _, 2, 1)) if id == second.input.id()
+    ));
+}
+
+#[tokio::test]
+async fn oneshot_input_closes() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(Once::default());
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&block.input).unwrap();
+    let mut outputs = system.take_output(&block.output).unwrap();
+
+    inputs.send(42).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(42)));
+    assert!(inputs.send(43).await.is_err());
+    assert_eq!(outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+/// A block that lists its port IDs without defining its ports.
+#[derive(Default)]
+struct Undefined {
+    input: Input<i64>,
+    output: Outputs<i64, -1, 1>,
+}
+
+impl BlockName for Undefined {
+    fn name(&self) -> Cow<'_, str> {
+        "Undefined".into()
+    }
+}
+
+impl BlockDefinition for Undefined {
+    fn inputs(&self) -> Vec<InputPortId> {
+        vec![self.input.id()]
+    }
+
+    fn outputs(&self) -> Vec<OutputPortId> {
+        vec![self.output.id()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Undefined {
+    async fn execute(&self, _inputs: BlockInputs, _outputs: BlockOutputs) -> Result {
+        Ok(())
+    }
+}
+
+#[test]
+fn undefined_ports_are_unbounded() {
+    let mut builder = SystemBuilder::new();
+    builder.register(Undefined::default());
+    assert!(builder.build().is_ok());
+}
+
+/// A block that tries to send two numbers on a one-shot output port.
+#[derive(Default)]
+struct Twice {
+    output: Output<i64>,
+}
+
+impl BlockName for Twice {
+    fn name(&self) -> Cow<'_, str> {
+        "Twice".into()
+    }
+}
+
+impl BlockDefinition for Twice {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.output).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Twice {
+    async fn execute(&self, _inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let output = outputs.take(&self.output).unwrap();
+        output.send(1).await?;
+        let error = output.send(2).await.unwrap_err();
+        assert_eq!(error.error(), SendError::Exhausted);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn oneshot_output_exhausts() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(Twice::default());
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let mut outputs = system.take_output(&block.output).unwrap();
+    system.execute().await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(1)));
+    assert_eq!(outputs.recv().await, Ok(None));
+}
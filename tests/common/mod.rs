@@ -0,0 +1,60 @@
+// This is free and unencumbered software released into the public domain.
+
+#![allow(dead_code)]
+
+use async_flow::{
+    Result,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, Inputs, Outputs,
+        PortDefinition,
+    },
+};
+use std::borrow::Cow;
+
+/// A block that forwards integers.
+pub type Forward = Forwarder<i64>;
+
+/// A block that forwards its input messages, checking that its runtime
+/// ports carry the names and labels of its ports.
+///
+/// Its input port must be connected at least `MIN` times.
+pub struct Forwarder<T, const MIN: isize = 0> {
+    pub input: Inputs<T, -1, MIN>,
+    pub output: Outputs<T>,
+}
+
+impl<T, const MIN: isize> Default for Forwarder<T, MIN> {
+    fn default() -> Self {
+        Self {
+            input: Inputs::default(),
+            output: Outputs::default(),
+        }
+    }
+}
+
+impl<T, const MIN: isize> BlockName for Forwarder<T, MIN> {
+    fn name(&self) -> Cow<'_, str> {
+        "Forward".into()
+    }
+}
+
+impl<T: Send + 'static, const MIN: isize> BlockDefinition for Forwarder<T, MIN> {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.input).into(), (&self.output).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static, const MIN: isize> Block for Forwarder<T, MIN> {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        assert_eq!(input.name().as_deref(), self.input.name());
+        assert_eq!(input.label().as_deref(), self.input.label());
+        let output = outputs.take(&self.output).unwrap();
+        assert_eq!(output.name().as_deref(), self.output.name());
+        while let Some(x) = input.recv().await? {
+            output.send(x).await?;
+        }
+        Ok(())
+    }
+}
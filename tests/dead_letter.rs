@@ -0,0 +1,179 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "std", feature = "tokio"))]
+
+mod common;
+
+use common::Forward;
+
+use async_flow::{
+    DeadLetterPort, FanOut, OutputPort, Result, SendError,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, DeadLetter, InputPortId,
+        Inputs, OutputPortId, Outputs, SystemBuilder,
+    },
+    tokio::Channel,
+};
+use core::time::Duration;
+use std::borrow::Cow;
+
+/// A block that forwards the dead letters it receives.
+#[derive(Default)]
+struct Audit {
+    input: Inputs<DeadLetter<i64>>,
+    output: Outputs<DeadLetter<i64>>,
+}
+
+impl BlockName for Audit {
+    fn name(&self) -> Cow<'_, str> {
+        "Audit".into()
+    }
+}
+
+impl BlockDefinition for Audit {
+    fn inputs(&self) -> Vec<InputPortId> {
+        vec![self.input.id()]
+    }
+
+    fn outputs(&self) -> Vec<OutputPortId> {
+        vec![self.output.id()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Audit {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        let output = outputs.take(&self.output).unwrap();
+        while let Some(letter) = input.recv().await? {
+            output.send(letter).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn assert_diverted(mut builder: SystemBuilder, forward: &Forward, audit: &Audit) {
+    builder.export(&forward.input).unwrap();
+    builder.export(&forward.output).unwrap();
+    builder.export(&audit.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&forward.input).unwrap();
+    let outputs = system.take_output(&forward.output).unwrap();
+    let mut letters = system.take_output(&audit.output).unwrap();
+
+    drop(outputs);
+    inputs.send(42).await.unwrap();
+    let letter = letters.recv().await.unwrap().unwrap();
+    assert_eq!(letter.source, forward.output.id());
+    assert_eq!(letter.error, SendError::Disconnected);
+    assert_eq!(letter.message, 42);
+    drop(inputs);
+    assert_eq!(letters.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_dead_letter() {
+    let mut builder = SystemBuilder::new();
+    let forward = builder.register(Forward::default());
+    let audit = builder.register(Audit::default());
+    builder
+        .connect_dead_letter(&forward.output, &audit.input)
+        .unwrap();
+    assert_diverted(builder, &forward, &audit).await;
+}
+
+#[tokio::test]
+async fn system_wide_dead_letter() {
+    let mut builder = SystemBuilder::new();
+    let forward = builder.register(Forward::default());
+    let audit = builder.register(Audit::default());
+    builder.dead_letter(&audit.input).unwrap();
+    assert_diverted(builder, &forward, &audit).await;
+}
+
+#[tokio::test]
+async fn broadcast_dead_letter() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Forward::default());
+    let second = builder.register(Forward::default());
+    let audit = builder.register(Audit::default());
+    builder.fan_out(&first.output, FanOut::broadcast()).unwrap();
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.export(&first.output).unwrap();
+    builder
+        .connect_dead_letter(&first.output, &audit.input)
+        .unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.output).unwrap();
+    builder.export(&audit.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&first.input).unwrap();
+    let skipped = system.take_output(&first.output).unwrap();
+    let mut outputs = system.take_output(&second.output).unwrap();
+    let mut letters = system.take_output(&audit.output).unwrap();
+
+    drop(skipped);
+    inputs.send(42).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(42)));
+    let letter = letters.recv().await.unwrap().unwrap();
+    assert_eq!(letter.source, first.output.id());
+    assert_eq!(letter.message, 42);
+    drop(inputs);
+    assert_eq!(outputs.recv().await, Ok(None));
+    assert_eq!(letters.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn dead_letter_port_diverts_every_send() {
+    let source = Outputs::<i64>::default().id();
+    let (outputs, inputs) = Channel::<i64>::bounded(1).into_inner();
+    let (sink, mut letters) = Channel::<DeadLetter<i64>>::bounded(4).into_inner();
+    drop(inputs);
+    let port = DeadLetterPort::new(Box::new(outputs), source, Box::new(sink));
+
+    let (sent, also_sent) = tokio::join!(port.send(1), port.send(2));
+    assert_eq!((sent, also_sent), (Ok(()), Ok(())));
+    let timeout = Duration::from_millis(10);
+    assert_eq!(port.send_timeout(3, timeout).await, Ok(()));
+    assert_eq!(port.try_send(4), Ok(()));
+    drop(port);
+
+    let mut diverted = Vec::new();
+    while let Some(letter) = letters.recv().await.unwrap() {
+        assert_eq!(letter.source, source);
+        diverted.push(letter.message);
+    }
+    diverted.sort();
+    assert_eq!(diverted, [1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn dead_letter_port_diverts_after_waiting() {
+    let source = Outputs::<i64>::default().id();
+    let (outputs, inputs) = Channel::<i64>::bounded(1).into_inner();
+    let (sink, mut letters) = Channel::<DeadLetter<i64>>::bounded(4).into_inner();
+    let port = DeadLetterPort::new(Box::new(outputs), source, Box::new(sink));
+
+    let timeout = Duration::from_millis(10);
+    assert_eq!(port.send_timeout(1, timeout).await, Ok(()));
+    assert_eq!(port.send_timeout(2, timeout).await, Ok(()));
+    let letter = letters.recv().await.unwrap().unwrap();
+    assert_eq!((letter.error, letter.message), (SendError::Timeout, 2));
+
+    let timeout = Duration::from_secs(30);
+    let (sent, ()) = tokio::join!(port.send_timeout(3, timeout), async move {
+        tokio::task::yield_now().await;
+        drop(inputs);
+    });
+    assert_eq!(sent, Ok(()));
+    let letter = letters.recv().await.unwrap().unwrap();
+    assert_eq!((letter.error, letter.message), (SendError::Disconnected, 3));
+}
@@ -0,0 +1,187 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "executor", feature = "tokio"))]
+
+use async_flow::{
+    Error, SendError, Task,
+    executor::{Channel, System},
+};
+use core::time::Duration;
+
+fn spawner() -> impl Fn(Task) + Send + Sync + 'static {
+    let runtime = tokio::runtime::Handle::current();
+    move |task| {
+        runtime.spawn(task);
+    }
+}
+
+#[tokio::test]
+async fn execute_connects_blocks() {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    let result = System::run(spawner(), |s| {
+        s.spawn(async move {
+            for n in 0..3 {
+                outputs.send(n).await?;
+            }
+            Ok(())
+        });
+        s.spawn(async move {
+            let mut sum = 0;
+            while let Some(n) = inputs.recv().await? {
+                sum += n;
+            }
+            assert_eq!(sum, 3);
+            Ok(())
+        });
+    })
+    .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn execute_fails_fast() {
+    let mut system = System::new(spawner());
+    let straggler = system.spawn(std::future::pending());
+    system.spawn(async { Err(SendError::Closed.into()) });
+    assert!(matches!(
+        system.execute().await,
+        Err(Error::Send(SendError::Closed))
+    ));
+    assert!(straggler.is_aborted());
+}
+
+#[tokio::test]
+async fn execute_reports_panics() {
+    let mut system = System::new(spawner());
+    system.spawn(async { panic!("block panicked") });
+    assert!(matches!(system.execute().await, Err(Error::Other(_))));
+}
+
+#[tokio::test]
+async fn shutdown_aborts_stragglers() {
+    let mut system = System::new(spawner());
+    system.spawn(std::future::pending());
+    system.shutdown_handle().shutdown(Duration::from_millis(10));
+    assert!(system.execute().await.is_ok());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn execute_on_scheduler() {
+    use async_flow::{Spawner, tokio::ParallelScheduler};
+    use std::sync::Arc;
+
+    let scheduler = Arc::new(ParallelScheduler::new().unwrap());
+    let spawner = Arc::clone(&scheduler);
+    let system = System::build(
+        move |task| spawner.spawn(task),
+        |s| {
+            s.spawn(async { Ok(()) });
+        },
+    );
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    assert!(runtime.block_on(system.execute()).is_ok());
+}
+
+/// Records the interleaving of the messages of two producers, running on a
+/// deterministic scheduler.
+#[cfg(feature = "serial")]
+fn trace(seed: u64) -> Vec<(char, i64)> {
+    use async_flow::tokio::DeterministicScheduler;
+    use std::sync::{Arc, Mutex};
+
+    let mut scheduler = DeterministicScheduler::new(seed);
+    let (outputs, mut inputs) = Channel::<(char, i64)>::bounded(1).into_inner();
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let events = Arc::clone(&trace);
+    let system = System::build(scheduler.spawner(), |s| {
+        for name in ['a', 'b'] {
+            let outputs = outputs.clone();
+            s.spawn(async move {
+                for n in 0..3 {
+                    outputs.send((name, n)).await?;
+                }
+                Ok(())
+            });
+        }
+        s.spawn(async move {
+            while let Some(event) = inputs.recv().await? {
+                events.lock().unwrap().push(event);
+            }
+            Ok(())
+        });
+    });
+    drop(outputs);
+    assert!(scheduler.run_system(system).is_ok());
+    trace.lock().unwrap().clone()
+}
+
+#[cfg(feature = "serial")]
+#[test]
+fn deterministic_replay() {
+    assert_eq!(trace(42).len(), 6);
+    assert_eq!(trace(42), trace(42));
+    let traces: std::collections::HashSet<_> = (0..16).map(trace).collect();
+    assert!(traces.len() > 1);
+}
+
+#[cfg(feature = "serial")]
+#[test]
+fn deterministic_backoff() {
+    use async_flow::{RestartPolicy, tokio::DeterministicScheduler};
+
+    let mut scheduler = DeterministicScheduler::new(0);
+    let clock = scheduler.clock();
+    let mut system = System::new(scheduler.spawner());
+    let policy = RestartPolicy::OnFailure {
+        max_retries: 2,
+        backoff: Duration::from_secs(3600),
+    };
+    let mut runs = 0;
+    let supervisor = system.spawn_supervised(policy, move || {
+        runs += 1;
+        let result = match runs {
+            1 | 2 => Err(SendError::Closed.into()),
+            _ => Ok(()),
+        };
+        async move { result }
+    });
+    assert!(scheduler.run_system(system).is_ok());
+    assert_eq!(supervisor.restarts(), 2);
+    assert_eq!(clock.now(), Duration::from_secs(3 * 3600));
+}
+
+#[cfg(feature = "serial")]
+#[test]
+fn deterministic_timeouts() {
+    use async_flow::{TryRecvError, TrySendError, tokio::DeterministicScheduler};
+
+    let mut scheduler = DeterministicScheduler::new(0);
+    let clock = scheduler.clock();
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    let mut system = System::new(scheduler.spawner());
+    system.spawn(async move {
+        let timeout = Duration::from_secs(60);
+        let received = inputs.recv_timeout(timeout).await;
+        assert!(matches!(received, Err(TryRecvError::Timeout)));
+        outputs.send(1).await?;
+        let sent = outputs.send_timeout(2, timeout).await;
+        assert!(matches!(sent, Err(TrySendError::Timeout(2))));
+        Ok(())
+    });
+    assert!(scheduler.run_system(system).is_ok());
+    assert_eq!(clock.now(), Duration::from_secs(2 * 60));
+}
+
+#[cfg(feature = "serial")]
+#[test]
+fn deterministic_stall() {
+    use async_flow::tokio::DeterministicScheduler;
+
+    let mut scheduler = DeterministicScheduler::new(0);
+    let mut system = System::new(scheduler.spawner());
+    system.spawn(std::future::pending());
+    assert!(matches!(scheduler.run_system(system), Err(Error::Other(_))));
+}
@@ -0,0 +1,106 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use async_flow::{
+    FanOut, InputPort,
+    tokio::{Channel, System},
+};
+
+#[tokio::test]
+async fn round_robin() {
+    let mut system = System::new();
+    let (outputs, inputs) = Channel::bounded(4).into_inner();
+    let (left_outputs, mut left) = Channel::bounded(4).into_inner();
+    let (right_outputs, mut right) = Channel::bounded(4).into_inner();
+    system.fan_out(
+        inputs,
+        vec![left_outputs, right_outputs],
+        FanOut::round_robin(),
+    );
+
+    for x in 1..=4 {
+        outputs.send(x).await.unwrap();
+    }
+    drop(outputs);
+    system.execute().await.unwrap();
+
+    assert_eq!(left.recv_all().await, Ok(vec![1, 3]));
+    assert_eq!(right.recv_all().await, Ok(vec![2, 4]));
+}
+
+#[tokio::test]
+async fn partition() {
+    let mut system = System::new();
+    let (outputs, inputs) = Channel::bounded(4).into_inner();
+    let (left_outputs, mut left) = Channel::bounded(4).into_inner();
+    let (right_outputs, mut right) = Channel::bounded(4).into_inner();
+    let policy = FanOut::partition(|(key, _): &(&str, i32)| *key);
+    system.fan_out(inputs, vec![left_outputs, right_outputs], policy);
+
+    for message in [("a", 1), ("b", 2), ("a", 3), ("b", 4)] {
+        outputs.send(message).await.unwrap();
+    }
+    drop(outputs);
+    system.execute().await.unwrap();
+
+    let (left, right) = (
+        left.recv_all().await.unwrap(),
+        right.recv_all().await.unwrap(),
+    );
+    assert_eq!(left.len() + right.len(), 4);
+    for partition in [left, right] {
+        assert!(partition.windows(2).all(|pair| pair[0].0 == pair[1].0));
+    }
+}
+
+#[tokio::test]
+async fn round_robin_reroutes() {
+    let mut system = System::new();
+    let (outputs, inputs) = Channel::bounded(4).into_inner();
+    let (left_outputs, left) = Channel::bounded(4).into_inner();
+    let (right_outputs, mut right) = Channel::bounded(4).into_inner();
+    drop(left);
+    system.fan_out(
+        inputs,
+        vec![left_outputs, right_outputs],
+        FanOut::round_robin(),
+    );
+
+    for x in 1..=4 {
+        outputs.send(x).await.unwrap();
+    }
+    drop(outputs);
+    system.execute().await.unwrap();
+
+    assert_eq!(right.recv_all().await, Ok(vec![1, 2, 3, 4]));
+}
+
+#[tokio::test]
+async fn partition_reroutes() {
+    let mut system = System::new();
+    let (outputs, inputs) = Channel::bounded(4).into_inner();
+    let (left_outputs, left) = Channel::bounded(4).into_inner();
+    let (right_outputs, mut right) = Channel::bounded(4).into_inner();
+    drop(left);
+    let policy = FanOut::partition(|(key, _): &(&str, i32)| *key);
+    system.fan_out(inputs, vec![left_outputs, right_outputs], policy);
+
+    for message in [("a", 1), ("b", 2), ("a", 3), ("b", 4)] {
+        outputs.send(message).await.unwrap();
+    }
+    drop(outputs);
+    system.execute().await.unwrap();
+
+    assert_eq!(right.recv_all().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn no_outputs() {
+    let mut system = System::new();
+    let (outputs, inputs) = Channel::bounded(4).into_inner();
+    system.fan_out(inputs, vec![], FanOut::<i32>::round_robin());
+    system.execute().await.unwrap();
+
+    assert!(outputs.send(1).await.is_err());
+}
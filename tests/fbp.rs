@@ -0,0 +1,163 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "serde", feature = "tokio"))]
+
+mod common;
+
+use common::Forward;
+
+use async_flow::{
+    FanOutKind, Result,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, BlockRegistry, FbpErrorKind,
+        FbpGraph, GraphError, Inputs, PortDefinition, SourcePosition, SystemBuildError,
+        SystemGraph,
+    },
+};
+use std::borrow::Cow;
+
+/// A block whose input port must be connected.
+#[derive(Default)]
+struct Required {
+    input: Inputs<i64, -1, 1>,
+}
+
+impl BlockName for Required {
+    fn name(&self) -> Cow<'_, str> {
+        "required".into()
+    }
+}
+
+impl BlockDefinition for Required {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.input).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Required {
+    async fn execute(&self, _inputs: BlockInputs, _outputs: BlockOutputs) -> Result {
+        Ok(())
+    }
+}
+
+fn registry() -> BlockRegistry {
+    let mut registry = BlockRegistry::new();
+    registry.register(Forward::default);
+    registry.register(Required::default);
+    registry.register_message::<i64>();
+    registry
+}
+
+fn position(line: usize, column: usize) -> SourcePosition {
+    SourcePosition { line, column }
+}
+
+#[test]
+fn round_trip() {
+    let source = "
+        # A pipeline of two blocks.
+        INPORT=first.0:IN
+        OUTPORT=second.0:OUT
+        '1' -> 0 first(Forward) 0 -> 0 second(Forward)
+        'it\\'s' -> 0 third(Forward), third 0 -> 0 second
+    ";
+    let graph = SystemGraph::from_fbp(source).unwrap();
+    assert_eq!(graph.blocks.len(), 3);
+    assert_eq!(graph.blocks["first"].r#type, "Forward");
+    assert_eq!(graph.connections.len(), 2);
+    assert_eq!(graph.inputs, ["first.0"]);
+    assert_eq!(graph.outputs, ["second.0"]);
+    assert_eq!(graph.export_names["first.0"], "IN");
+    assert_eq!(graph.packets[0].data, 1);
+    assert_eq!(graph.packets[1].data, "it's");
+
+    let printed = graph.to_fbp().unwrap();
+    assert!(printed.contains("INPORT=first.0:IN\n"));
+    assert_eq!(SystemGraph::from_fbp(&printed).unwrap(), graph);
+}
+
+#[test]
+fn unsupported_names() {
+    let graph = SystemGraph::from_fbp("a(Forward) 0 -> 0 b(Forward)").unwrap();
+    let mut renamed = graph.clone();
+    let block = renamed.blocks.remove("b").unwrap();
+    renamed.blocks.insert("my-b".into(), block);
+    renamed.connections[0].to = "my-b.0".into();
+    assert!(matches!(renamed.to_fbp(), Err(GraphError::Unsupported(_))));
+
+    let mut exported = graph;
+    exported.outputs.push("b.0".into());
+    exported.export_names.insert("b.0".into(), "OUT PUT".into());
+    assert!(matches!(exported.to_fbp(), Err(GraphError::Unsupported(_))));
+}
+
+#[test]
+fn unsupported_fan_out() {
+    let mut graph = SystemGraph::from_fbp("a(Forward) 0 -> 0 b(Forward)").unwrap();
+    graph.fan_outs.insert("a.0".into(), FanOutKind::RoundRobin);
+    assert!(matches!(graph.to_fbp(), Err(GraphError::Unsupported(_))));
+}
+
+#[test]
+fn syntax_error() {
+    let error = FbpGraph::parse("a(Forward) 0 -> 0 b(Forward)\na 0 -> -> 0 b").unwrap_err();
+    assert_eq!(error.position, position(2, 8));
+    assert!(matches!(error.kind, FbpErrorKind::Unexpected { .. }));
+    assert_eq!(
+        error.to_string(),
+        "2:8: unexpected `->`, expected an input port name"
+    );
+
+    let error = FbpGraph::parse("'unterminated -> 0 a(Forward)").unwrap_err();
+    assert_eq!(error.position, position(1, 1));
+    assert!(matches!(error.kind, FbpErrorKind::Unterminated(_)));
+}
+
+#[test]
+fn undeclared_node() {
+    let error = FbpGraph::parse("a 0 -> 0 b(Forward)")
+        .unwrap()
+        .to_graph()
+        .unwrap_err();
+    assert_eq!(error.position, position(1, 1));
+    assert!(matches!(error.kind, FbpErrorKind::UndeclaredNode(_)));
+}
+
+#[test]
+fn located_build_errors() {
+    let graph = FbpGraph::parse("a(Forward) 0 -> 0 b(Forward)\na 7 -> 0 b").unwrap();
+    let error = graph.build(&mut registry()).unwrap_err();
+    assert_eq!(error.position, position(2, 5));
+    assert!(matches!(
+        error.kind,
+        FbpErrorKind::Graph(GraphError::UnknownPort(_))
+    ));
+
+    let graph = FbpGraph::parse("a(Forward)\n  b(required)").unwrap();
+    let error = graph.build(&mut registry()).unwrap_err();
+    assert_eq!(error.position, position(2, 3));
+    assert!(matches!(
+        error.kind,
+        FbpErrorKind::Graph(GraphError::Build(SystemBuildError::UnderconnectedInput(..)))
+    ));
+}
+
+#[tokio::test]
+async fn execute() {
+    let source = "
+        OUTPORT=second.0:OUT
+        '1' -> 0 first(Forward) 0 -> 0 second(Forward)
+        '2' -> 0 first
+    ";
+    let definition = FbpGraph::parse(source)
+        .unwrap()
+        .build(&mut registry())
+        .unwrap();
+
+    let mut system = definition.prepare();
+    let (&output, _) = definition.outputs.iter().next().unwrap();
+    let mut outputs = system.take_output_port::<i64>(output).unwrap();
+    assert_eq!(outputs.recv_all().await, Ok(vec![1, 2]));
+    system.execute().await.unwrap();
+}
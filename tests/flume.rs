@@ -0,0 +1,241 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "flume")]
+
+use async_flow::{
+    Error, FailurePolicy, PortEvent, Result, SendError, SendMessageError, TrySendError,
+    flume::{Channel, System},
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, InputPortId, Inputs,
+        OutputPortId, Outputs, SystemBuilder,
+    },
+};
+use core::time::Duration;
+use std::borrow::Cow;
+
+/// A block that doubles its input numbers.
+#[derive(Default)]
+struct Double {
+    input: Inputs<i64>,
+    output: Outputs<i64>,
+}
+
+impl BlockName for Double {
+    fn name(&self) -> Cow<'_, str> {
+        "Double".into()
+    }
+}
+
+impl BlockDefinition for Double {
+    fn inputs(&self) -> Vec<InputPortId> {
+        vec![self.input.id()]
+    }
+
+    fn outputs(&self) -> Vec<OutputPortId> {
+        vec![self.output.id()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Double {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        let output = outputs.take(&self.output).unwrap();
+        while let Some(x) = input.recv().await? {
+            output.send(x * 2).await?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn recv_lifecycle_events() {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(4).into_inner();
+    outputs.blocking_send(42).unwrap();
+    drop(outputs);
+    let result = System::build(|s| {
+        s.spawn(async move {
+            assert!(matches!(
+                inputs.recv_event().await,
+                Ok(Some(PortEvent::Connect))
+            ));
+            assert!(matches!(
+                inputs.recv_event().await,
+                Ok(Some(PortEvent::Message(42)))
+            ));
+            assert!(matches!(
+                inputs.recv_event().await,
+                Ok(Some(PortEvent::Disconnect))
+            ));
+            assert!(matches!(inputs.recv_event().await, Ok(None)));
+            Ok(())
+        });
+    })
+    .blocking_execute();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn oneshot_exhausts() {
+    let (outputs, mut inputs) = Channel::<i64>::oneshot().into_inner();
+    outputs.blocking_send(42).unwrap();
+    assert!(outputs.is_exhausted());
+    assert!(outputs.try_send(43).is_err());
+    assert_eq!(inputs.blocking_recv().unwrap(), Some(42));
+    assert_eq!(inputs.blocking_recv().unwrap(), None);
+}
+
+#[test]
+fn failed_send_keeps_limit() {
+    let (outputs, inputs) = Channel::<i64>::oneshot().into_inner();
+    drop(inputs);
+    assert_eq!(
+        outputs.blocking_send(42),
+        Err(SendMessageError::new(SendError::Disconnected, 42))
+    );
+    assert!(!outputs.is_exhausted());
+    assert_eq!(
+        outputs.blocking_send(43),
+        Err(SendMessageError::new(SendError::Disconnected, 43))
+    );
+}
+
+#[test]
+fn recv_timeout() {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    let result = System::build(|s| {
+        s.spawn(async move {
+            let timeout = Duration::from_millis(10);
+            assert!(inputs.recv_timeout(timeout).await.is_err());
+            // Long timeouts that don't elapse are cancelled, not left pending:
+            for x in 0..100 {
+                outputs.send(x).await?;
+                let timeout = Duration::from_secs(30);
+                assert_eq!(inputs.recv_timeout(timeout).await, Ok(Some(x)));
+            }
+            Ok(())
+        });
+    })
+    .blocking_execute();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn send_timeout() {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    let result = System::build(|s| {
+        s.spawn(async move {
+            let timeout = Duration::from_millis(10);
+            assert_eq!(outputs.send_timeout(1, timeout).await, Ok(()));
+            assert_eq!(
+                outputs.send_timeout(2, timeout).await,
+                Err(TrySendError::Timeout(2))
+            );
+            assert_eq!(inputs.recv().await, Ok(Some(1)));
+            assert_eq!(outputs.send_timeout(3, timeout).await, Ok(()));
+            drop(inputs);
+            assert_eq!(
+                outputs.send_timeout(4, timeout).await,
+                Err(TrySendError::Disconnected(4))
+            );
+            Ok(())
+        });
+    })
+    .blocking_execute();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn execute_connects_blocks() {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    let result = System::build(|s| {
+        s.spawn(async move {
+            for n in 0..3 {
+                outputs.send(n).await?;
+            }
+            Ok(())
+        });
+        s.spawn(async move {
+            let mut sum = 0;
+            while let Some(n) = inputs.recv().await? {
+                sum += n;
+            }
+            assert_eq!(sum, 3);
+            Ok(())
+        });
+    })
+    .blocking_execute();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn execute_fails_fast() {
+    let mut system = System::new();
+    system.spawn(std::future::pending());
+    system.spawn(async { Err(SendError::Closed.into()) });
+    assert!(matches!(
+        system.blocking_execute(),
+        Err(Error::Send(SendError::Closed))
+    ));
+}
+
+#[test]
+fn execute_reports_panics() {
+    let mut system = System::new();
+    system.spawn(async { panic!("block panicked") });
+    assert!(matches!(system.blocking_execute(), Err(Error::Other(_))));
+}
+
+#[test]
+fn execute_collects_all() {
+    let mut system = System::new();
+    system.set_failure_policy(FailurePolicy::CollectAll);
+    system.spawn(async { Err(SendError::Closed.into()) });
+    system.spawn(async { Ok(()) });
+    system.spawn(async { Err(SendError::Disconnected.into()) });
+    match system.blocking_execute() {
+        Err(Error::Aggregate(errors)) => assert_eq!(errors.len(), 2),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn shutdown_aborts_stragglers() {
+    let mut system = System::new();
+    system.spawn(std::future::pending());
+    let shutdown = system.shutdown_handle();
+    shutdown.shutdown(Duration::from_millis(10));
+    assert!(shutdown.is_shutdown());
+    assert!(system.blocking_execute().is_ok());
+}
+
+#[test]
+fn prepare_and_execute() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Double::default());
+    let second = builder.register(Double::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = System::from(&definition);
+    let inputs = system.take_input(&first.input).unwrap();
+    let mut outputs = system.take_output(&second.output).unwrap();
+
+    let result = System::build(|s| {
+        s.spawn(async move {
+            for x in 1..=3 {
+                inputs.send(x).await?;
+                assert_eq!(outputs.recv().await, Ok(Some(x * 4)));
+            }
+            drop(inputs);
+            assert_eq!(outputs.recv().await, Ok(None));
+            Ok(())
+        });
+    })
+    .blocking_execute();
+    assert!(result.is_ok());
+
+    system.blocking_execute().unwrap();
+}
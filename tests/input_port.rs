@@ -0,0 +1,95 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use async_flow::{
+    InputPort, PortEvent, RecvError, TryRecvError,
+    tokio::{Channel, Outputs, System},
+};
+use core::time::Duration;
+
+#[tokio::test]
+async fn try_recv() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    assert_eq!(inputs.try_recv(), Err(TryRecvError::Empty));
+    outputs.send(42).await.unwrap();
+    assert_eq!(inputs.try_recv(), Ok(Some(42)));
+    drop(outputs);
+    assert_eq!(inputs.try_recv(), Ok(None));
+}
+
+#[tokio::test]
+async fn recv_timeout() {
+    let (outputs, mut inputs) = Channel::<i32>::bounded(1).into_inner();
+    let timeout = Duration::from_millis(10);
+    assert_eq!(
+        inputs.recv_timeout(timeout).await,
+        Err(TryRecvError::Timeout)
+    );
+    outputs.send(42).await.unwrap();
+    assert_eq!(inputs.recv_timeout(timeout).await, Ok(Some(42)));
+}
+
+#[tokio::test]
+async fn recv_via_trait() {
+    let (outputs, inputs) = Channel::bounded(2).into_inner();
+    let mut inputs: Box<dyn InputPort<i32> + Send> = Box::new(inputs);
+    outputs.send(1).await.unwrap();
+    outputs.send(2).await.unwrap();
+    drop(outputs);
+    assert_eq!(inputs.recv_all().await, Ok(vec![1, 2]));
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn into_stream() {
+    use tokio_stream::StreamExt;
+    let (outputs, inputs) = Channel::bounded(2).into_inner();
+    outputs.send(1).await.unwrap();
+    outputs.send(2).await.unwrap();
+    drop(outputs);
+    let messages: Vec<i32> = inputs.into_stream().collect().await;
+    assert_eq!(messages, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn blocking_recv() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    let receiver = std::thread::spawn(move || (inputs.blocking_recv(), inputs.blocking_recv()));
+    outputs.send(42).await.unwrap();
+    drop(outputs);
+    assert_eq!(receiver.join().unwrap(), (Ok(Some(42)), Ok(None)));
+}
+
+#[tokio::test]
+async fn blocking_recv_from_system_task() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    outputs.send(42).await.unwrap();
+    drop(outputs);
+    let mut system = System::new();
+    system.spawn(async move {
+        assert_eq!(inputs.blocking_recv(), Err(RecvError::AsyncContext));
+        let receiver = tokio::task::spawn_blocking(move || inputs.blocking_recv());
+        assert_eq!(receiver.await.unwrap(), Ok(Some(42)));
+        Ok(())
+    });
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn recv_lifecycle_events() {
+    let (first, mut inputs) = Channel::bounded(4).into_inner();
+    let second: Outputs<_> = Outputs::from(first.as_ref());
+    second.send_event(PortEvent::Connect).await.unwrap();
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Connect)));
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Connect)));
+
+    drop(first);
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Disconnect)));
+    second.send(42).await.unwrap();
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Message(42))));
+
+    drop(second);
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Disconnect)));
+    assert_eq!(inputs.recv_event().await, Ok(None));
+}
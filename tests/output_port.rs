@@ -0,0 +1,150 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use async_flow::{
+    OutputPort, PortEvent, PortState, SendError, SendMessageError, TrySendError,
+    tokio::{Channel, System},
+};
+use core::time::Duration;
+
+#[tokio::test]
+async fn try_send() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    assert_eq!(outputs.try_send(1), Ok(()));
+    assert_eq!(outputs.try_send(2), Err(TrySendError::Full(2)));
+    assert_eq!(inputs.recv().await, Ok(Some(1)));
+}
+
+#[tokio::test]
+async fn try_send_hands_back_message() {
+    let (outputs, inputs) = Channel::bounded(1).into_inner();
+    drop(inputs);
+    let error = outputs.try_send(String::from("hello")).unwrap_err();
+    assert_eq!(error.as_str(), "Disconnected");
+    assert_eq!(error.into_inner(), "hello");
+}
+
+#[tokio::test]
+async fn send_hands_back_message() {
+    let (outputs, inputs) = Channel::bounded(1).into_inner();
+    drop(inputs);
+    let error = outputs.send(String::from("hello")).await.unwrap_err();
+    assert_eq!(error.error(), SendError::Disconnected);
+    assert_eq!(error.into_inner(), "hello");
+}
+
+#[test]
+fn try_send_error_from_send_error() {
+    let error = TrySendError::from(SendError::AsyncContext);
+    assert_eq!(error, TrySendError::AsyncContext(()));
+    assert_eq!(
+        error.to_string(),
+        "cannot block on sending a message from within an async context"
+    );
+    assert_eq!(
+        TrySendError::Full(1).to_string(),
+        "failed to send message on full port"
+    );
+}
+
+#[tokio::test]
+async fn send_timeout() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    let timeout = Duration::from_millis(10);
+    assert_eq!(outputs.send_timeout(1, timeout).await, Ok(()));
+    assert_eq!(
+        outputs.send_timeout(2, timeout).await,
+        Err(TrySendError::Timeout(2))
+    );
+    assert_eq!(inputs.recv().await, Ok(Some(1)));
+}
+
+#[tokio::test]
+async fn send_via_trait() {
+    let (outputs, mut inputs) = Channel::bounded(2).into_inner();
+    let outputs: Box<dyn OutputPort<i32> + Send> = Box::new(outputs);
+    outputs.send_event(PortEvent::Message(1)).await.unwrap();
+    outputs.try_send(2).unwrap();
+    assert_eq!(inputs.recv().await, Ok(Some(1)));
+    assert_eq!(inputs.recv().await, Ok(Some(2)));
+}
+
+#[tokio::test]
+async fn oneshot_exhausts() {
+    let (outputs, mut inputs) = Channel::oneshot().into_inner();
+    assert_eq!(outputs.send(1).await, Ok(()));
+    assert_eq!(outputs.state(), PortState::Disconnected);
+    assert_eq!(
+        outputs.send(2).await,
+        Err(SendMessageError::new(SendError::Exhausted, 2))
+    );
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Connect)));
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Message(1))));
+    assert_eq!(inputs.recv_event().await, Ok(Some(PortEvent::Disconnect)));
+    assert_eq!(inputs.state(), PortState::Disconnected);
+    assert_eq!(inputs.recv().await, Ok(None));
+}
+
+#[tokio::test]
+async fn failed_send_keeps_limit() {
+    let (outputs, inputs) = Channel::oneshot().into_inner();
+    drop(inputs);
+    assert_eq!(
+        outputs.send(1).await,
+        Err(SendMessageError::new(SendError::Disconnected, 1))
+    );
+    assert!(!outputs.is_exhausted());
+    let sender = std::thread::spawn(move || outputs.blocking_send(2));
+    assert_eq!(
+        sender.join().unwrap(),
+        Err(SendMessageError::new(SendError::Disconnected, 2))
+    );
+}
+
+#[tokio::test]
+async fn blocking_send() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    let sender = std::thread::spawn(move || outputs.blocking_send(2));
+    assert_eq!(inputs.recv().await, Ok(Some(2)));
+    assert_eq!(sender.join().unwrap(), Ok(()));
+}
+
+#[tokio::test]
+async fn blocking_send_from_system_task() {
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    let mut system = System::new();
+    system.spawn(async move {
+        assert_eq!(
+            outputs.blocking_send(1),
+            Err(SendMessageError::new(SendError::AsyncContext, 1))
+        );
+        let sender = tokio::task::spawn_blocking(move || outputs.blocking_send(2));
+        Ok(sender.await.unwrap()?)
+    });
+    system.execute().await.unwrap();
+    assert_eq!(inputs.recv().await, Ok(Some(2)));
+}
+
+#[test]
+fn blocking_send_within_runtime() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let _guard = runtime.enter();
+    let (outputs, mut inputs) = Channel::bounded(1).into_inner();
+    assert_eq!(outputs.blocking_send(1), Ok(()));
+    assert_eq!(inputs.blocking_recv(), Ok(Some(1)));
+}
+
+#[tokio::test]
+async fn clones_share_limit() {
+    let (outputs, mut inputs) = Channel::oneshot().into_inner();
+    let clone = outputs.clone();
+    assert_eq!(outputs.send(1).await, Ok(()));
+    assert_eq!(
+        clone.send(2).await,
+        Err(SendMessageError::new(SendError::Exhausted, 2))
+    );
+    assert_eq!(inputs.recv().await, Ok(Some(1)));
+}
@@ -0,0 +1,111 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "std", feature = "tokio"))]
+
+mod common;
+
+use common::Forwarder;
+
+use async_flow::model::{BlockDefinition, Inputs, Outputs, SystemBuildError, SystemBuilder};
+use dogma::{MaybeLabeled, MaybeNamed};
+
+/// A forwarding block with named ports.
+fn named_forward() -> Forwarder<i64, 1> {
+    Forwarder {
+        input: Inputs::named("in").with_label("Numbers"),
+        output: Outputs::named("out"),
+    }
+}
+
+#[test]
+fn port_definitions() {
+    let block = named_forward();
+    let ports = block.ports();
+    assert_eq!(ports[0].name, Some("in"));
+    assert_eq!(ports[0].label, Some("Numbers"));
+    assert_eq!(ports[1].name, Some("out"));
+    assert_eq!(ports[1].label, None);
+    assert!(format!("{:?}", block.input).contains("\"in\""));
+}
+
+#[test]
+fn build_errors() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(named_forward());
+    builder.export(&block.output).unwrap();
+    let error = builder.build().unwrap_err();
+    assert!(matches!(
+        error,
+        SystemBuildError::UnderconnectedInput(_, Some("in"), 0, 1)
+    ));
+    assert_eq!(error.port_name(), Some("in"));
+    assert!(
+        error
+            .to_string()
+            .contains(&format!("{} \"in\"", block.input.id()))
+    );
+}
+
+#[test]
+fn runtime_ports() {
+    let (output, input) = async_flow::tokio::Channel::<i64>::bounded(1).into_inner();
+    let (output, input) = (output.with_name("out"), input.with_label("Numbers"));
+    assert_eq!(output.name().as_deref(), Some("out"));
+    assert_eq!(input.name(), None);
+    assert_eq!(input.label().as_deref(), Some("Numbers"));
+    assert!(format!("{output:?}").contains("\"out\""));
+}
+
+#[tokio::test]
+async fn wired_ports() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(named_forward());
+    let second = builder.register(named_forward());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.initial_packet(&first.input, 42).unwrap();
+    let output = builder.export_output(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let mut outputs = system.take_output_port::<i64>(output).unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(42)));
+    system.execute().await.unwrap();
+}
+
+#[test]
+fn diagrams() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(named_forward());
+    let second = builder.register(named_forward());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.initial_packet(&first.input, 42).unwrap();
+    builder.export(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mermaid = definition.to_mermaid();
+    assert!(mermaid.contains("block0 -->|\"out → Numbers: i64\"| block1"));
+    let dot = definition.to_dot();
+    assert!(
+        dot.contains("\"forward0\" [label=\"{{<i0> Numbers}|forward0: Forward|{<o0> out}}\"];")
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn graph_references() {
+    use async_flow::model::{BlockRegistry, SystemGraph};
+
+    let mut registry = BlockRegistry::new();
+    registry.register(named_forward);
+    registry.register_message::<i64>();
+    let graph =
+        SystemGraph::from_fbp("'1' -> IN a(Forward) OUT -> in b(Forward) 0 -> IN c(Forward)")
+            .unwrap();
+    let definition = graph.build(&mut registry).unwrap();
+    assert_eq!(definition.connections.len(), 2);
+
+    let graph = definition.to_graph().unwrap();
+    assert_eq!(graph.connections[0].from, "a.out");
+    assert_eq!(graph.connections[0].to, "b.in");
+    assert_eq!(graph.packets[0].to, "a.in");
+}
@@ -0,0 +1,132 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "tokio", feature = "serial", feature = "parallel"))]
+
+use async_flow::{
+    Scheduler, Spawner,
+    tokio::{Channel, DeterministicScheduler, ParallelScheduler, SerialScheduler, System},
+};
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+fn schedulers() -> Vec<Box<dyn Scheduler>> {
+    vec![
+        Box::new(SerialScheduler::new().unwrap()),
+        Box::new(ParallelScheduler::new().unwrap()),
+    ]
+}
+
+#[test]
+fn run_to_completion() {
+    for mut scheduler in schedulers() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        for n in 0..3 {
+            let tx = tx.clone();
+            scheduler.spawn(Box::pin(async move { tx.send(n).unwrap() }));
+        }
+        drop(tx);
+        scheduler.run();
+        let mut results: Vec<i64> = rx.try_iter().collect();
+        results.sort();
+        assert_eq!(results, [0, 1, 2]);
+    }
+}
+
+#[test]
+fn shutdown_aborts_tasks() {
+    for mut scheduler in schedulers() {
+        scheduler.spawn(Box::pin(std::future::pending()));
+        scheduler.shutdown();
+        scheduler.run(); // returns, as nothing is left to run
+    }
+}
+
+fn sum_system(scheduler: &impl AsRef<tokio::runtime::Handle>) -> System {
+    let (outputs, mut inputs) = Channel::<i64>::bounded(1).into_inner();
+    System::build_on(scheduler, |s| {
+        s.spawn(async move {
+            for n in 0..3 {
+                outputs.send(n).await?;
+            }
+            Ok(())
+        });
+        s.spawn(async move {
+            let mut sum = 0;
+            while let Some(n) = inputs.recv().await? {
+                sum += n;
+            }
+            assert_eq!(sum, 3);
+            Ok(())
+        });
+    })
+}
+
+#[test]
+fn execute_on_serial() {
+    let scheduler = SerialScheduler::new().unwrap();
+    let system = sum_system(&scheduler);
+    assert!(system.execute_on(&scheduler).is_ok());
+}
+
+#[test]
+#[should_panic = "another runtime"]
+fn execute_on_another_scheduler() {
+    let system = sum_system(&SerialScheduler::new().unwrap());
+    let _ = system.execute_on(&SerialScheduler::new().unwrap());
+}
+
+#[test]
+fn run_system_on_parallel() {
+    let scheduler = ParallelScheduler::new().unwrap();
+    let system = sum_system(&scheduler);
+    assert!(scheduler.run_system(system).is_ok());
+}
+
+/// Records the interleaving of the messages of two producers.
+fn trace(seed: u64) -> Vec<(char, i64)> {
+    let mut scheduler = DeterministicScheduler::new(seed);
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    for name in ['a', 'b'] {
+        let tx = tx.clone();
+        scheduler.spawn(Box::pin(async move {
+            for n in 0..3 {
+                tx.send((name, n)).await.unwrap();
+            }
+        }));
+    }
+    drop(tx);
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let events = Arc::clone(&trace);
+    scheduler.spawn(Box::pin(async move {
+        while let Some(event) = rx.recv().await {
+            events.lock().unwrap().push(event);
+        }
+    }));
+    scheduler.run();
+    trace.lock().unwrap().clone()
+}
+
+#[test]
+fn deterministic_replay() {
+    assert_eq!(trace(42).len(), 6);
+    assert_eq!(trace(42), trace(42));
+    let traces: std::collections::HashSet<_> = (0..16).map(trace).collect();
+    assert!(traces.len() > 1);
+}
+
+#[test]
+fn deterministic_virtual_time() {
+    let mut scheduler = DeterministicScheduler::new(0);
+    let clock = scheduler.clock();
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    for (name, secs) in [("slow", 3600), ("fast", 60)] {
+        let (clock, trace) = (clock.clone(), Arc::clone(&trace));
+        scheduler.spawn(Box::pin(async move {
+            clock.sleep(Duration::from_secs(secs)).await;
+            trace.lock().unwrap().push(name);
+        }));
+    }
+    scheduler.run();
+    assert_eq!(*trace.lock().unwrap(), ["fast", "slow"]);
+    assert_eq!(clock.now(), Duration::from_secs(3600));
+}
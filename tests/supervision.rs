@@ -0,0 +1,208 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "std", feature = "tokio"))]
+
+use async_flow::{
+    Error, PortEvent, RestartPolicy, Result,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, InputPortId, Inputs,
+        OutputPortId, Outputs, SystemBuilder,
+    },
+    tokio::System,
+};
+use core::time::Duration;
+use std::{
+    borrow::Cow,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// A block that forwards up to `N` input numbers, failing on negative ones.
+#[derive(Default)]
+struct NonNegative<const N: isize = -1> {
+    input: Inputs<i64, N>,
+    output: Outputs<i64>,
+}
+
+impl<const N: isize> BlockName for NonNegative<N> {
+    fn name(&self) -> Cow<'_, str> {
+        "NonNegative".into()
+    }
+}
+
+impl<const N: isize> BlockDefinition for NonNegative<N> {
+    fn inputs(&self) -> Vec<InputPortId> {
+        vec![self.input.id()]
+    }
+
+    fn outputs(&self) -> Vec<OutputPortId> {
+        vec![self.output.id()]
+    }
+}
+
+#[async_trait::async_trait]
+impl<const N: isize> Block for NonNegative<N> {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        let output = outputs.take(&self.output).unwrap();
+        while let Some(x) = input.recv().await? {
+            if x < 0 {
+                return Err(Error::Other("negative input".into()));
+            }
+            output.send(x).await?;
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn restart_keeps_ports_connected() {
+    let policy = RestartPolicy::OnFailure {
+        max_retries: 3,
+        backoff: Duration::from_millis(1),
+    };
+    let mut builder = SystemBuilder::new();
+    let block = builder.register_supervised(NonNegative::<-1>::default(), policy);
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let supervisor = system.supervisor(&block).unwrap();
+    let inputs = system.take_input(&block.input).unwrap();
+    let mut outputs = system.take_output(&block.output).unwrap();
+
+    inputs.send(1).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(1)));
+    inputs.send(-1).await.unwrap();
+    inputs.send(2).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(2)));
+    assert_eq!(supervisor.restarts(), 1);
+    drop(inputs);
+    assert_eq!(outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn restart_gives_up_after_max_retries() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut system = System::new();
+    let supervisor = system.spawn_supervised(
+        RestartPolicy::OnFailure {
+            max_retries: 2,
+            backoff: Duration::from_millis(1),
+        },
+        {
+            let runs = Arc::clone(&runs);
+            move || {
+                runs.fetch_add(1, Ordering::Relaxed);
+                async { Err(Error::Other("failed".into())) }
+            }
+        },
+    );
+    assert!(matches!(system.execute().await, Err(Error::Other(_))));
+    assert_eq!(runs.load(Ordering::Relaxed), 3);
+    assert_eq!(supervisor.restarts(), 2);
+}
+
+#[tokio::test]
+async fn never_restarts_by_default() {
+    let mut system = System::new();
+    let supervisor = system.spawn_supervised(RestartPolicy::default(), || async {
+        Err(Error::Other("failed".into()))
+    });
+    assert!(system.execute().await.is_err());
+    assert_eq!(supervisor.restarts(), 0);
+}
+
+#[tokio::test]
+async fn always_stops_once_inputs_are_drained() {
+    let policy = RestartPolicy::Always {
+        max_restarts: 10,
+        backoff: Duration::ZERO,
+    };
+    let mut builder = SystemBuilder::new();
+    let block = builder.register_supervised(NonNegative::<-1>::default(), policy);
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let supervisor = system.supervisor(&block).unwrap();
+    let inputs = system.take_input(&block.input).unwrap();
+    let mut outputs = system.take_output(&block.output).unwrap();
+
+    for x in [1, -1, 2] {
+        inputs.send(x).await.unwrap();
+    }
+    drop(inputs);
+    let mut events = Vec::new();
+    while let Some(event) = outputs.recv_event().await.unwrap() {
+        if !event.is_connect() {
+            events.push(event);
+        }
+    }
+    assert_eq!(
+        events,
+        [
+            PortEvent::Message(1),
+            PortEvent::Failure,
+            PortEvent::Message(2),
+            PortEvent::Disconnect,
+        ]
+    );
+
+    system.execute().await.unwrap();
+    assert_eq!(supervisor.restarts(), 1);
+}
+
+#[tokio::test]
+async fn always_gives_up_after_max_restarts() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut system = System::new();
+    let supervisor = system.spawn_supervised(
+        RestartPolicy::Always {
+            max_restarts: 3,
+            backoff: Duration::ZERO,
+        },
+        {
+            let runs = Arc::clone(&runs);
+            move || {
+                runs.fetch_add(1, Ordering::Relaxed);
+                async { Ok(()) }
+            }
+        },
+    );
+    system.execute().await.unwrap();
+    assert_eq!(runs.load(Ordering::Relaxed), 4);
+    assert_eq!(supervisor.restarts(), 3);
+}
+
+#[tokio::test]
+async fn restart_keeps_message_budget() {
+    let policy = RestartPolicy::OnFailure {
+        max_retries: 3,
+        backoff: Duration::ZERO,
+    };
+    let mut builder = SystemBuilder::new();
+    let block = builder.register_supervised(NonNegative::<2>::default(), policy);
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&block.input).unwrap();
+    let mut outputs = system.take_output(&block.output).unwrap();
+
+    inputs.send(-1).await.unwrap();
+    inputs.send(1).await.unwrap();
+    inputs.send(2).await.unwrap();
+    drop(inputs);
+    assert_eq!(outputs.recv().await, Ok(Some(1)));
+    assert_eq!(outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
@@ -0,0 +1,55 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use async_flow::{Error, FailurePolicy, SendError, tokio::System};
+use core::time::Duration;
+
+#[tokio::test]
+async fn execute_succeeds() {
+    let mut system = System::new();
+    system.spawn(async { Ok(()) });
+    assert!(system.execute().await.is_ok());
+}
+
+#[tokio::test]
+async fn execute_fails_fast() {
+    let mut system = System::new();
+    system.spawn(std::future::pending());
+    system.spawn(async { Err(SendError::Closed.into()) });
+    assert!(matches!(
+        system.execute().await,
+        Err(Error::Send(SendError::Closed))
+    ));
+}
+
+#[tokio::test]
+async fn execute_reports_panics() {
+    let mut system = System::new();
+    system.spawn(async { panic!("block panicked") });
+    assert!(matches!(system.execute().await, Err(Error::Join(_))));
+}
+
+#[tokio::test]
+async fn execute_collects_all() {
+    let mut system = System::new();
+    system.set_failure_policy(FailurePolicy::CollectAll);
+    system.spawn(async { Err(SendError::Closed.into()) });
+    system.spawn(async { Ok(()) });
+    system.spawn(async { Err(SendError::Disconnected.into()) });
+    match system.execute().await {
+        Err(Error::Aggregate(errors)) => assert_eq!(errors.len(), 2),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn shutdown_aborts_stragglers() {
+    let mut system = System::new();
+    system.spawn(std::future::pending());
+    let shutdown = system.shutdown_handle();
+    let execution = tokio::spawn(system.execute());
+    shutdown.shutdown(Duration::from_millis(10));
+    assert!(shutdown.is_shutdown());
+    assert!(execution.await.unwrap().is_ok());
+}
@@ -0,0 +1,247 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use common::Forwarder;
+
+use async_flow::{
+    FanOut, Result,
+    model::{
+        Block, BlockDefinition, BlockInputs, BlockName, BlockOutputs, InputPortId, Inputs,
+        OutputPortId, Outputs, SystemBuildError, SystemBuilder,
+    },
+};
+use core::{cell::Cell, time::Duration};
+use std::borrow::Cow;
+
+/// A block that doubles its input numbers.
+#[derive(Default)]
+struct Double {
+    input: Inputs<i64>,
+    output: Outputs<i64>,
+}
+
+impl BlockName for Double {
+    fn name(&self) -> Cow<'_, str> {
+        "Double".into()
+    }
+}
+
+impl BlockDefinition for Double {
+    fn inputs(&self) -> Vec<InputPortId> {
+        vec![self.input.id()]
+    }
+
+    fn outputs(&self) -> Vec<OutputPortId> {
+        vec![self.output.id()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Double {
+    async fn execute(&self, mut inputs: BlockInputs, mut outputs: BlockOutputs) -> Result {
+        let mut input = inputs.take(&self.input).unwrap();
+        let output = outputs.take(&self.output).unwrap();
+        while let Some(x) = input.recv().await? {
+            output.send(x * 2).await?;
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn prepare_and_execute() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Double::default());
+    let second = builder.register(Double::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&first.input).unwrap();
+    let mut outputs = system.take_output(&second.output).unwrap();
+
+    for x in 1..=3 {
+        inputs.send(x).await.unwrap();
+        assert_eq!(outputs.recv().await, Ok(Some(x * 4)));
+    }
+    drop(inputs);
+    assert_eq!(outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn fan_in() {
+    let mut builder = SystemBuilder::new();
+    let left = builder.register(Double::default());
+    let right = builder.register(Double::default());
+    let merge = builder.register(Double::default());
+    builder.connect(&left.output, &merge.input).unwrap();
+    builder.connect(&right.output, &merge.input).unwrap();
+    builder.export(&left.input).unwrap();
+    builder.export(&right.input).unwrap();
+    builder.export(&merge.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let left_inputs = system.take_input(&left.input).unwrap();
+    let right_inputs = system.take_input(&right.input).unwrap();
+    let mut outputs = system.take_output(&merge.output).unwrap();
+
+    left_inputs.send(1).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(4)));
+    drop(left_inputs);
+
+    // The merged port remains connected as long as any upstream is:
+    right_inputs.send(10).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(40)));
+    drop(right_inputs);
+    assert_eq!(outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn fan_out_broadcast() {
+    let mut builder = SystemBuilder::new();
+    let split = builder.register(Double::default());
+    let left = builder.register(Double::default());
+    let right = builder.register(Double::default());
+    builder.fan_out(&split.output, FanOut::broadcast()).unwrap();
+    builder.connect(&split.output, &left.input).unwrap();
+    builder.connect(&split.output, &right.input).unwrap();
+    builder.export(&split.input).unwrap();
+    builder.export(&left.output).unwrap();
+    builder.export(&right.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&split.input).unwrap();
+    let mut left_outputs = system.take_output(&left.output).unwrap();
+    let mut right_outputs = system.take_output(&right.output).unwrap();
+
+    inputs.send(1).await.unwrap();
+    assert_eq!(left_outputs.recv().await, Ok(Some(4)));
+    assert_eq!(right_outputs.recv().await, Ok(Some(4)));
+    drop(inputs);
+    assert_eq!(left_outputs.recv().await, Ok(None));
+    assert_eq!(right_outputs.recv().await, Ok(None));
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn fan_out_per_connection() {
+    let mut builder = SystemBuilder::new();
+    let split = builder.register(Double::default());
+    let left = builder.register(Double::default());
+    let right = builder.register(Double::default());
+    let policy = FanOut::round_robin;
+    builder
+        .connect_with(&split.output, &left.input, policy())
+        .unwrap();
+    builder
+        .connect_with(&split.output, &right.input, policy())
+        .unwrap();
+    assert!(matches!(
+        builder.connect_with(&split.output, &right.input, FanOut::broadcast()),
+        Err(SystemBuildError::AlreadyFannedOutOutput(..))
+    ));
+    builder.export(&split.input).unwrap();
+    builder.export(&left.output).unwrap();
+    builder.export(&right.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&split.input).unwrap();
+    let mut left_outputs = system.take_output(&left.output).unwrap();
+    let mut right_outputs = system.take_output(&right.output).unwrap();
+
+    inputs.send(1).await.unwrap();
+    inputs.send(2).await.unwrap();
+    drop(inputs);
+    let mut outputs = left_outputs.recv_all().await.unwrap();
+    assert_eq!(outputs.len(), 1);
+    outputs.extend(right_outputs.recv_all().await.unwrap());
+    outputs.sort();
+    assert_eq!(outputs, vec![4, 8]);
+
+    system.execute().await.unwrap();
+}
+
+#[tokio::test]
+async fn fan_out_without_policy() {
+    let mut builder = SystemBuilder::new();
+    let split = builder.register(Double::default());
+    let left = builder.register(Double::default());
+    let right = builder.register(Double::default());
+    builder.connect(&split.output, &left.input).unwrap();
+    assert!(matches!(
+        builder.connect(&split.output, &right.input),
+        Err(SystemBuildError::AlreadyConnectedOutput(..))
+    ));
+}
+
+#[tokio::test]
+async fn shutdown_closes_exported_inputs() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(Double::default());
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input(&block.input).unwrap();
+    let mut outputs = system.take_output(&block.output).unwrap();
+    let shutdown = system.shutdown_handle();
+    let execution = tokio::spawn(system.execute());
+
+    inputs.send(1).await.unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(2)));
+    shutdown.shutdown(Duration::from_secs(60));
+    assert_eq!(outputs.recv().await, Ok(None));
+    assert!(execution.await.unwrap().is_ok());
+    assert!(inputs.send(3).await.is_err());
+}
+
+#[tokio::test]
+async fn export_connected_output() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Double::default());
+    let second = builder.register(Double::default());
+    let third = builder.register(Double::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    assert!(matches!(
+        builder.export(&first.output),
+        Err(SystemBuildError::AlreadyConnectedOutput(..))
+    ));
+    builder.export(&second.output).unwrap();
+    assert!(matches!(
+        builder.connect(&second.output, &third.input),
+        Err(SystemBuildError::AlreadyConnectedOutput(..))
+    ));
+}
+
+#[tokio::test]
+async fn non_sync_messages() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Forwarder::<Cell<i64>>::default());
+    let second = builder.register(Forwarder::<Cell<i64>>::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    let input = builder.export_input(&first.input).unwrap();
+    let output = builder.export_output(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let mut system = definition.prepare();
+    let inputs = system.take_input_port::<Cell<i64>>(input).unwrap();
+    let mut outputs = system.take_output_port::<Cell<i64>>(output).unwrap();
+    inputs.send(Cell::new(42)).await.unwrap();
+    drop(inputs);
+    assert_eq!(outputs.recv().await.unwrap().map(|x| x.get()), Some(42));
+    system.execute().await.unwrap();
+}
@@ -0,0 +1,124 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::Forwarder;
+
+use async_flow::{
+    Result,
+    model::{
+        Block, BlockDefinition, BlockHandle, BlockInputs, BlockName, BlockOutputs, DeadLetter,
+        Inputs, Outputs, PortDefinition, SystemBuilder, SystemDefinition,
+    },
+};
+use std::{borrow::Cow, sync::Arc};
+
+/// A block that receives dead letters.
+#[derive(Default)]
+struct Audit {
+    input: Inputs<DeadLetter<String>>,
+}
+
+impl BlockName for Audit {
+    fn name(&self) -> Cow<'_, str> {
+        "Audit".into()
+    }
+}
+
+impl BlockDefinition for Audit {
+    fn ports(&self) -> Vec<PortDefinition> {
+        vec![(&self.input).into()]
+    }
+}
+
+#[async_trait::async_trait]
+impl Block for Audit {
+    async fn execute(&self, _inputs: BlockInputs, _outputs: BlockOutputs) -> Result {
+        Ok(())
+    }
+}
+
+fn definition() -> SystemDefinition {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Forwarder::<String>::default());
+    let second = builder.register(Forwarder::<String>::default());
+    let audit = builder.register(Audit::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder
+        .connect_dead_letter(&first.output, &audit.input)
+        .unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.output).unwrap();
+    builder.build().unwrap()
+}
+
+#[test]
+fn to_dot() {
+    assert_eq!(
+        definition().to_dot(),
+        r#"digraph {
+    rankdir=LR;
+    node [shape=record];
+    "forward0" [label="{{<i0> 0}|forward0: Forward|{<o0> 0}}"];
+    "forward1" [label="{{<i0> 0}|forward1: Forward|{<o0> 0}}"];
+    "audit2" [label="{{<i0> 0}|audit2: Audit}"];
+    "forward0":o0 -> "forward1":i0 [label="String"];
+    "forward0":o0 -> "audit2":i0 [label="DeadLetter<String>", style=dashed];
+    "__input0" [shape=point];
+    "__input0" -> "forward0":i0 [label="String"];
+    "__output1" [shape=point];
+    "forward1":o0 -> "__output1" [label="String"];
+}
+"#
+    );
+}
+
+#[test]
+fn to_mermaid() {
+    assert_eq!(
+        definition().to_mermaid(),
+        r#"flowchart LR
+    block0["forward0: Forward"]
+    block1["forward1: Forward"]
+    block2["audit2: Audit"]
+    block0 -->|"0 → 0: String"| block1
+    block0 -.->|"0 → 0: DeadLetter#lt;String#gt;"| block2
+    input0(( ))
+    input0 -->|"0: String"| block0
+    output1(( ))
+    block1 -->|"0: String"| output1
+"#
+    );
+}
+
+#[test]
+fn unowned_ports() {
+    let mut builder = SystemBuilder::new();
+    let input = Inputs::<String>::default();
+    let output = Outputs::<String>::default();
+    builder.register_port(input.id());
+    builder.register_port(output.id());
+    builder.connect(&output, &input).unwrap();
+    builder.export(&input).unwrap();
+    let definition = builder.build().unwrap();
+
+    let (input, output) = (input.id(), output.id());
+    let dot = definition.to_dot();
+    assert!(dot.contains(&format!(r#""__port {output}" -> "__port {input}""#)));
+    let mermaid = definition.to_mermaid();
+    assert!(mermaid.contains(&format!(r#"|"{output} → {input}: String"|"#)));
+}
+
+#[test]
+fn block_named_like_a_point() {
+    let mut builder = SystemBuilder::new();
+    let block = Arc::new(Forwarder::<String>::default());
+    builder.register_handle(BlockHandle::new(block.clone()).with_name("input0"));
+    builder.export(&block.input).unwrap();
+    builder.export(&block.output).unwrap();
+    let dot = builder.build().unwrap().to_dot();
+    assert!(dot.contains(r#""__input0" -> "input0":i0"#));
+    assert!(dot.contains(r#""input0":o0 -> "__output1""#));
+}
@@ -0,0 +1,114 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(all(feature = "serde", feature = "tokio"))]
+
+mod common;
+
+use common::Forward;
+
+use async_flow::model::{
+    Block, GraphError, GraphLoader, InitialPacket, PortType, SystemBuildError, SystemBuilder,
+    SystemGraph,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+struct Loader;
+
+impl GraphLoader for Loader {
+    fn block(&mut self, r#type: &str) -> Option<Arc<dyn Block>> {
+        match r#type {
+            "Forward" => Some(Arc::new(Forward::default())),
+            _ => None,
+        }
+    }
+
+    fn packet(&mut self, port_type: PortType, data: &Value) -> Option<InitialPacket> {
+        if port_type != PortType::of::<i64>() {
+            return None;
+        }
+        InitialPacket::from_json::<i64>(data.clone()).ok()
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut builder = SystemBuilder::new();
+    let first = builder.register(Forward::default());
+    let second = builder.register(Forward::default());
+    builder.connect(&first.output, &second.input).unwrap();
+    builder.export(&first.input).unwrap();
+    builder.export(&second.output).unwrap();
+    let definition = builder.build().unwrap();
+
+    let graph = definition.to_graph().unwrap();
+    assert_eq!(graph.blocks.len(), 2);
+    assert_eq!(graph.connections.len(), 1);
+    assert_eq!(graph.inputs, ["forward0.0"]);
+    assert_eq!(graph.outputs, ["forward1.0"]);
+
+    let json = graph.to_json().unwrap();
+    let parsed = SystemGraph::from_json(&json).unwrap();
+    assert_eq!(parsed, graph);
+    let rebuilt = parsed.build(&mut Loader).unwrap();
+    assert_eq!(rebuilt.to_graph().unwrap(), graph);
+}
+
+#[test]
+fn unknown_block_type() {
+    let graph = SystemGraph::from_json(r#"{"blocks": {"x": {"type": "Missing"}}}"#).unwrap();
+    assert!(matches!(
+        graph.build(&mut Loader),
+        Err(GraphError::UnknownBlockType(_))
+    ));
+}
+
+#[test]
+fn unserializable_packet() {
+    let mut builder = SystemBuilder::new();
+    let block = builder.register(Forward::default());
+    builder.initial_packet(&block.input, 42).unwrap();
+    builder.export(&block.output).unwrap();
+    let definition = builder.build().unwrap();
+    assert!(matches!(
+        definition.to_graph(),
+        Err(GraphError::UnserializablePacket(_))
+    ));
+}
+
+#[test]
+fn mistyped_dead_letter() {
+    let graph = SystemGraph::from_json(
+        r#"{
+            "blocks": {"first": {"type": "Forward"}, "second": {"type": "Forward"}},
+            "dead_letters": [{"from": "first.0", "to": "second.0"}]
+        }"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        graph.build(&mut Loader),
+        Err(GraphError::Build(SystemBuildError::MismatchedType(..)))
+    ));
+}
+
+#[tokio::test]
+async fn initial_packets() {
+    let graph = SystemGraph::from_json(
+        r#"{
+            "blocks": {"forward": {"type": "Forward"}},
+            "outputs": ["forward.0"],
+            "packets": [{"to": "forward.0", "data": 1}, {"to": "forward.0", "data": 2}]
+        }"#,
+    )
+    .unwrap();
+    let definition = graph.build(&mut Loader).unwrap();
+    assert_eq!(definition.to_graph().unwrap(), graph);
+
+    let mut system = definition.prepare();
+    let (&output, _) = definition.outputs.iter().next().unwrap();
+    let mut outputs = system.take_output_port::<i64>(output).unwrap();
+    assert_eq!(outputs.recv().await, Ok(Some(1)));
+    assert_eq!(outputs.recv().await, Ok(Some(2)));
+    assert_eq!(outputs.recv().await, Ok(None));
+    system.execute().await.unwrap();
+}